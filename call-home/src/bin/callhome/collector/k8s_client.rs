@@ -1,4 +1,4 @@
-use k8s_openapi::api::core::v1::{Namespace, Node};
+use k8s_openapi::api::core::v1::{ConfigMap, Namespace, Node, Secret};
 use kube::{
     api::{Api, ListParams},
     Client,
@@ -41,6 +41,16 @@ impl K8sClient {
         Ok(nodes_count)
     }
 
+    /// Get the api of the Secrets of the namespace.
+    pub(crate) fn secrets(&self, namespace: &str) -> Api<Secret> {
+        Api::namespaced(self.client.clone(), namespace)
+    }
+
+    /// Get the api of the ConfigMaps of the namespace.
+    pub(crate) fn config_maps(&self, namespace: &str) -> Api<ConfigMap> {
+        Api::namespaced(self.client.clone(), namespace)
+    }
+
     /// Get kube-system namespace uid.
     pub(crate) async fn get_cluster_id(&self) -> Result<String, K8sResourceError> {
         let namespace_api: Api<Namespace> = Api::all(self.client.clone());
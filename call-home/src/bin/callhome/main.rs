@@ -1,4 +1,5 @@
 mod collector;
+mod schedule;
 mod transmitter;
 
 use crate::{
@@ -10,7 +11,12 @@ use crate::{
         },
         storage_rest::list_all_volumes,
     },
-    transmitter::*,
+    schedule::{LastSentStore, Schedule},
+    transmitter::{
+        client::{ExtraHeader, Receiver, ReceiverOptions},
+        spool::{Spool, SpoolFormat, SpoolTarget},
+        *,
+    },
 };
 use clap::Parser;
 use collector::report_models::{MayastorManagedDisks, Nexus, StorageMedia, StorageNodes};
@@ -19,6 +25,7 @@ use openapi::tower::client::{ApiClient, Configuration};
 use sha256::digest;
 use std::{
     collections::{HashMap, VecDeque},
+    path::PathBuf,
     sync::{Arc, Mutex},
     time::Duration,
 };
@@ -29,7 +36,8 @@ use tokio_retry::{
 };
 use tracing::{error, info, Event, Level, Subscriber};
 use tracing_subscriber::{
-    layer::Context, prelude::__tracing_subscriber_SubscriberExt, EnvFilter, Layer,
+    fmt::writer::BoxMakeWriter, layer::Context, prelude::__tracing_subscriber_SubscriberExt,
+    EnvFilter, Layer,
 };
 use url::Url;
 use utils::{package_description, version_info_str};
@@ -52,7 +60,97 @@ struct CliArgs {
     /// The endpoint to fetch events stats.
     #[clap(long, short)]
     aggregator_url: Option<Url>,
+
+    /// Remote collection endpoints the report is sent to, the call-home endpoint by default.
+    #[clap(long, value_delimiter = ',')]
+    receiver_url: Vec<Url>,
+
+    /// PEM bundle of the CA certificates the remote collection endpoints are verified against, in
+    /// addition to the built-in ones.
+    #[clap(long)]
+    receiver_ca_bundle: Option<PathBuf>,
+
+    /// Extra header sent along with the report, as `<name>: <value>`, which overrides the
+    /// call-home header of the same name.
+    #[clap(long)]
+    receiver_header: Vec<ExtraHeader>,
+
+    /// HTTP proxy the report is sent through.
+    #[clap(long)]
+    receiver_proxy: Option<Url>,
+
+    /// Writes each report to a file of the directory, for the reports to be collected where the
+    /// remote collection endpoint cannot be reached.
+    #[clap(long, conflicts_with = "spool_secret")]
+    spool_dir: Option<PathBuf>,
+
+    /// Writes each report to a key of the Secret in the namespace, which is created if it does
+    /// not exist.
+    #[clap(long)]
+    spool_secret: Option<String>,
+
+    /// Forms in which the reports are written to the spool.
+    #[clap(long, value_enum, default_value_t = SpoolFormat::Encrypted)]
+    spool_format: SpoolFormat,
+
+    /// Number of the most recent reports retained in the spool.
+    #[clap(long, default_value_t = 7)]
+    spool_retention: usize,
+
+    /// Total size in bytes of the most recent reports retained in the spool, within the 1MiB
+    /// limit of a Secret.
+    #[clap(long, default_value_t = 983040)]
+    spool_max_size: u64,
+
+    /// Interval between the reports.
+    #[clap(long, default_value = DEFAULT_REPORT_INTERVAL)]
+    report_interval: humantime::Duration,
+
+    /// Cron expression of the times of the reports in UTC, with a seconds field, eg: `0 0 3 * * *`
+    /// for 3am every day. Overrides the report interval.
+    #[clap(long, value_parser = schedule::parse_cron)]
+    report_schedule: Option<Schedule>,
+
+    /// Maximum random delay added to the time of each report, to spread the reports over time.
+    #[clap(long, default_value = "0s")]
+    report_jitter: humantime::Duration,
+
+    /// ConfigMap in the namespace the time of the last report is persisted to, so that restarts
+    /// do not generate reports ahead of the schedule.
+    #[clap(long, default_value = DEFAULT_STATE_CONFIG_MAP)]
+    state_config_map: String,
+
+    /// Prints the unencrypted report once, in the given format, and exits without sending or
+    /// spooling it.
+    #[clap(
+        long,
+        visible_alias = "dry-run",
+        value_enum,
+        num_args = 0..=1,
+        default_missing_value = "json"
+    )]
+    print_report: Option<PrintFormat>,
+}
+
+/// Formats the report can be printed in.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum PrintFormat {
+    /// Pretty JSON.
+    Json,
+    /// YAML.
+    Yaml,
+}
+
+impl PrintFormat {
+    /// Renders the report in the format.
+    fn render(&self, report: &Report) -> anyhow::Result<String> {
+        Ok(match self {
+            Self::Json => serde_json::to_string_pretty(report)?,
+            Self::Yaml => serde_yaml::to_string(report)?,
+        })
+    }
 }
+
 impl CliArgs {
     fn args() -> Self {
         CliArgs::parse()
@@ -63,32 +161,34 @@ const ERR_LOG_BUF_CAPACITY: usize = 100;
 
 #[tokio::main]
 async fn main() {
+    let args = CliArgs::args();
     let logs = Arc::new(Mutex::new(VecDeque::with_capacity(ERR_LOG_BUF_CAPACITY)));
     let vec_layer = LogsLayer::new(logs.clone());
 
+    // Keep the stdout to the report only, when it is printed.
+    let writer = match args.print_report {
+        Some(_) => BoxMakeWriter::new(std::io::stderr),
+        None => BoxMakeWriter::new(std::io::stdout),
+    };
     let subscriber = tracing_subscriber::Registry::default()
         .with(vec_layer)
-        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_subscriber::fmt::layer().with_writer(writer))
         .with(EnvFilter::from_default_env());
 
     tracing::subscriber::set_global_default(subscriber).expect("setting tracing default failed");
 
-    if let Err(error) = run(logs).await {
+    if let Err(error) = run(args, logs).await {
         error!(?error, "failed call-home");
         std::process::exit(1);
     }
 }
 
-async fn run(logs: Arc<Mutex<VecDeque<LogEntry>>>) -> anyhow::Result<()> {
-    let args = CliArgs::args();
+async fn run(args: CliArgs, logs: Arc<Mutex<VecDeque<LogEntry>>>) -> anyhow::Result<()> {
     let version = release_version();
     let endpoint = args.endpoint;
     let aggregator_url = args.aggregator_url;
     let send_report = args.send_report;
-    let namespace = digest(args.namespace);
-    let sleep_duration = call_home_frequency();
-    let encryption_dir = encryption_dir();
-    let key_filepath = key_filepath();
+    let namespace = digest(args.namespace.clone());
 
     // Generate kubernetes client.
     let k8s_client = K8sClient::new()
@@ -100,13 +200,6 @@ async fn run(logs: Arc<Mutex<VecDeque<LogEntry>>>) -> anyhow::Result<()> {
         anyhow::anyhow!("failed to generate kubernetes cluster ID: {:?}", error)
     })?);
 
-    // Generate receiver API client.
-    let receiver = client::Receiver::new(&k8s_cluster_id)
-        .await
-        .map_err(|error| {
-            anyhow::anyhow!("failed to generate metrics receiver client: {:?}", error)
-        })?;
-
     // Generate Mayastor REST client.
     let config = Configuration::builder()
         .with_timeout(Duration::from_secs(30))
@@ -115,7 +208,80 @@ async fn run(logs: Arc<Mutex<VecDeque<LogEntry>>>) -> anyhow::Result<()> {
         .map_err(|error| anyhow::anyhow!("failed to create openapi configuration: {:?}", error))?;
     let client = openapi::clients::tower::ApiClient::new(config);
 
+    // Print the report instead of sending it, if it is previewed.
+    if let Some(format) = args.print_report {
+        let report = generate_report(
+            k8s_client,
+            client,
+            k8s_cluster_id,
+            namespace,
+            version,
+            aggregator_url,
+            logs,
+        )
+        .await;
+        println!("{}", format.render(&report)?);
+        return Ok(());
+    }
+    let key_filepath = key_filepath();
+
+    // Generate receiver API clients.
+    let receiver_options = ReceiverOptions {
+        ca_bundle: args.receiver_ca_bundle,
+        proxy: args.receiver_proxy,
+        headers: args.receiver_header,
+    };
+    let receiver_urls = match args.receiver_url.is_empty() {
+        true => vec![Url::parse(RECEIVER_ENDPOINT)?],
+        false => args.receiver_url,
+    };
+    let mut receivers = Vec::with_capacity(receiver_urls.len());
+    for url in receiver_urls {
+        let receiver = Receiver::new(&k8s_cluster_id, url, &receiver_options)
+            .await
+            .map_err(|error| {
+                anyhow::anyhow!("failed to generate metrics receiver client: {:?}", error)
+            })?;
+        receivers.push(receiver);
+    }
+
+    // Generate the spool of the reports, if any.
+    let spool_target = match (args.spool_dir, args.spool_secret) {
+        (Some(dir), _) => Some(SpoolTarget::Directory(dir)),
+        (None, Some(name)) => Some(SpoolTarget::Secret {
+            api: k8s_client.secrets(&args.namespace),
+            name,
+        }),
+        (None, None) => None,
+    };
+    let spool = spool_target.map(|target| {
+        Spool::new(
+            target,
+            args.spool_format,
+            args.spool_retention,
+            args.spool_max_size,
+        )
+    });
+
+    // Generate the schedule of the reports, starting from the last one generated, if any.
+    let schedule = args
+        .report_schedule
+        .unwrap_or(Schedule::Interval(args.report_interval.into()));
+    let report_jitter = args.report_jitter.into();
+    let last_sent_store = LastSentStore::new(
+        k8s_client.config_maps(&args.namespace),
+        args.state_config_map,
+    );
+    let mut last_sent = last_sent_store.get().await;
+
     loop {
+        // Block until next transmission window.
+        let next = schedule.next(last_sent, chrono::Utc::now());
+        let delay = (next - chrono::Utc::now()).to_std().unwrap_or_default()
+            + schedule::jitter(report_jitter);
+        info!(%next, ?delay, "Waiting for the next report");
+        sleep(delay).await;
+
         // Generate report.
         let report = generate_report(
             k8s_client.clone(),
@@ -128,25 +294,70 @@ async fn run(logs: Arc<Mutex<VecDeque<LogEntry>>>) -> anyhow::Result<()> {
         )
         .await;
 
-        // Encrypt data.
-        let encryption_dir = encryption_dir.clone();
-        let key_filepath = key_filepath.clone();
-        let output = tokio::task::spawn_blocking(move || {
-            encryption::encrypt(&report, &encryption_dir, &key_filepath)
-        })
-        .await?;
-        let output = output.map_err(|error| anyhow::anyhow!("encryption failed: {:?}", error))?;
-
-        // POST data to receiver API.
-        if send_report {
-            match receiver.post(output).await {
-                Ok(response) => info!(?response, "Success"),
-                Err(e) => error!(?e, "failed HTTP POST request"),
+        let report = Arc::new(report);
+
+        // Encrypt data, unless it is neither sent nor spooled encrypted. A failure is retried with
+        // the next report, rather than stopping the reports altogether.
+        let output = if send_report
+            || spool
+                .as_ref()
+                .is_some_and(|spool| spool.format().encrypted())
+        {
+            let report = report.clone();
+            let key_filepath = key_filepath.clone();
+            let output =
+                tokio::task::spawn_blocking(move || encryption::encrypt(&report, &key_filepath))
+                    .await;
+            match output {
+                Ok(Ok(output)) => Some(output),
+                Ok(Err(error)) => {
+                    error!(?error, "encryption failed");
+                    None
+                }
+                Err(error) => {
+                    error!(?error, "encryption task failed");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // Write data to the spool.
+        let mut spooled = false;
+        if let Some(spool) = &spool {
+            match spool.write(&report, output.as_deref()).await {
+                // The report is not fully spooled without the encrypted form it was due in.
+                Ok(()) => spooled = output.is_some() || !spool.format().encrypted(),
+                Err(error) => error!(?error, "failed to write the report to the spool"),
             }
         }
 
-        // Block until next transmission window.
-        sleep(sleep_duration).await;
+        // POST data to receiver APIs.
+        let mut posted = false;
+        if let (true, Some(output)) = (send_report, output) {
+            for receiver in &receivers {
+                match receiver.post(output.clone()).await {
+                    Ok(response) => {
+                        info!(?response, url = %receiver.url(), "Success");
+                        posted = true;
+                    }
+                    Err(e) => error!(?e, url = %receiver.url(), "failed HTTP POST request"),
+                }
+            }
+        }
+
+        // Wait for the next transmission window either way, but only persist the time of the
+        // report once it was delivered, for a restart to generate it again otherwise.
+        let sent = chrono::Utc::now();
+        let delivered = match send_report {
+            true => posted,
+            false => spooled,
+        };
+        if delivered {
+            last_sent_store.set(sent).await;
+        }
+        last_sent = Some(sent);
     }
 }
 
@@ -384,3 +595,25 @@ fn log_with_limit<T>(logs: &mut VecDeque<T>, message: T, limit: usize) {
     }
     logs.push_back(message);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::PrintFormat;
+    use crate::collector::report_models::Report;
+
+    #[test]
+    fn test_print_report() {
+        let report = Report::default();
+        let value = serde_json::to_value(&report).unwrap();
+        let json = PrintFormat::Json.render(&report).unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            value
+        );
+        let yaml = PrintFormat::Yaml.render(&report).unwrap();
+        assert_eq!(
+            serde_yaml::from_str::<serde_json::Value>(&yaml).unwrap(),
+            value
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::{
+    api::{Api, Patch, PatchParams},
+    core::ObjectMeta,
+};
+use obs::common::constants::{LAST_SENT_KEY, SCHEDULE_FIELD_MANAGER};
+use rand::Rng;
+use std::{collections::BTreeMap, str::FromStr, time::Duration};
+use tracing::{error, info};
+
+/// When the reports are generated.
+#[derive(Debug, Clone)]
+pub(crate) enum Schedule {
+    /// At a fixed interval since the last report.
+    Interval(Duration),
+    /// At the times of the cron expression.
+    Cron(Box<cron::Schedule>),
+}
+
+impl Schedule {
+    /// Returns the time of the next report, given the time the last one was generated at, if
+    /// any. A report which is overdue, or which was never generated on an interval schedule, is
+    /// due now.
+    pub(crate) fn next(
+        &self,
+        last_sent: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> DateTime<Utc> {
+        let next = match (self, last_sent) {
+            (Self::Interval(interval), Some(last_sent)) => chrono::Duration::from_std(*interval)
+                .ok()
+                .and_then(|interval| last_sent.checked_add_signed(interval)),
+            (Self::Interval(_), None) => Some(now),
+            (Self::Cron(schedule), last_sent) => schedule.after(&last_sent.unwrap_or(now)).next(),
+        };
+        next.map_or(now, |next| next.max(now))
+    }
+}
+
+/// Parses a cron expression with a seconds field, e.g. `0 0 3 * * *`.
+pub(crate) fn parse_cron(expression: &str) -> Result<Schedule, String> {
+    cron::Schedule::from_str(expression)
+        .map(|schedule| Schedule::Cron(Box::new(schedule)))
+        .map_err(|error| format!("invalid cron expression '{expression}': {error}"))
+}
+
+/// Returns a random duration of up to 'max', to spread the reports of different clusters.
+pub(crate) fn jitter(max: Duration) -> Duration {
+    rand::thread_rng().gen_range(Duration::ZERO ..= max)
+}
+
+/// ConfigMap the time of the last report is persisted to, so that restarts do not generate
+/// reports ahead of their schedule.
+pub(crate) struct LastSentStore {
+    api: Api<ConfigMap>,
+    name: String,
+}
+
+impl LastSentStore {
+    /// 'LastSentStore::new()' creates a new instance of LastSentStore, persisting to the
+    /// ConfigMap of the api with the name.
+    pub(crate) fn new(api: Api<ConfigMap>, name: String) -> Self {
+        Self { api, name }
+    }
+
+    /// Returns the persisted time of the last report, if any. The errors are logged, as if no
+    /// report was generated yet.
+    pub(crate) async fn get(&self) -> Option<DateTime<Utc>> {
+        let config_map = match self.api.get_opt(&self.name).await {
+            Ok(config_map) => config_map?,
+            Err(error) => {
+                error!(?error, name = %self.name, "failed to get the call-home state config map");
+                return None;
+            }
+        };
+        let last_sent = config_map.data?.remove(LAST_SENT_KEY)?;
+        match DateTime::parse_from_rfc3339(&last_sent) {
+            Ok(last_sent) => Some(last_sent.with_timezone(&Utc)),
+            Err(error) => {
+                error!(?error, %last_sent, "invalid time of the last call-home report");
+                None
+            }
+        }
+    }
+
+    /// Persists the time of the last report, creating the ConfigMap if it does not exist. The
+    /// errors are logged, as the reports are still generated on schedule until a restart.
+    pub(crate) async fn set(&self, last_sent: DateTime<Utc>) {
+        let config_map = ConfigMap {
+            metadata: ObjectMeta {
+                name: Some(self.name.clone()),
+                ..Default::default()
+            },
+            data: Some(BTreeMap::from([(
+                LAST_SENT_KEY.to_string(),
+                last_sent.to_rfc3339(),
+            )])),
+            ..Default::default()
+        };
+        let params = PatchParams::apply(SCHEDULE_FIELD_MANAGER).force();
+        match self
+            .api
+            .patch(&self.name, &params, &Patch::Apply(&config_map))
+            .await
+        {
+            Ok(_) => info!(%last_sent, "Persisted the time of the last call-home report"),
+            Err(error) => {
+                error!(?error, name = %self.name, "failed to update the call-home state config map")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_cron, Schedule};
+    use chrono::{DateTime, Duration, Utc};
+
+    fn time(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_schedule_next() {
+        let now = time("2023-10-02T12:00:00Z");
+        let interval = Schedule::Interval(Duration::hours(24).to_std().unwrap());
+        assert_eq!(interval.next(None, now), now);
+        assert_eq!(
+            interval.next(Some(time("2023-10-02T06:00:00Z")), now),
+            time("2023-10-03T06:00:00Z")
+        );
+        assert_eq!(interval.next(Some(time("2023-09-30T06:00:00Z")), now), now);
+
+        let cron = parse_cron("0 0 3 * * *").unwrap();
+        assert_eq!(cron.next(None, now), time("2023-10-03T03:00:00Z"));
+        assert_eq!(
+            cron.next(Some(time("2023-10-02T03:00:00Z")), now),
+            time("2023-10-03T03:00:00Z")
+        );
+        assert_eq!(cron.next(Some(time("2023-10-01T03:00:00Z")), now), now);
+        assert!(parse_cron("every day").is_err());
+    }
+}
@@ -2,6 +2,10 @@
 /// API endpoint.
 pub(crate) mod client;
 
-/// 'encryption' module contains helper function(s) which enable the use of the GnuPG
-/// encryption tool.
+/// 'encryption' module contains helper function(s) which encrypt the reports into OpenPGP
+/// messages.
 pub(crate) mod encryption;
+
+/// 'spool' module contains type(s) which write the reports to a local spool, for them to be
+/// collected where the receiver cannot be reached.
+pub(crate) mod spool;
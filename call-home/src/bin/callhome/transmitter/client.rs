@@ -1,52 +1,160 @@
 use chrono::prelude::*;
 use k8s_openapi::chrono;
 use obs::common::{constants::*, errors::ReceiverError};
-use reqwest::Response;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Certificate, Proxy, Response,
+};
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
+use std::{fs, path::PathBuf, str::FromStr};
+use url::Url;
 
-/// Struct used to make calls to the receiver API.
+/// Marker of the start of each certificate of a PEM bundle.
+const PEM_CERTIFICATE_BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+
+/// An extra header sent along with the reports, given as `<name>: <value>`.
+#[derive(Debug, Clone)]
+pub(crate) struct ExtraHeader {
+    name: HeaderName,
+    value: HeaderValue,
+}
+
+impl FromStr for ExtraHeader {
+    type Err = String;
+
+    fn from_str(header: &str) -> Result<Self, Self::Err> {
+        let Some((name, value)) = header.split_once(':') else {
+            return Err(format!("'{header}' is not of the form <name>: <value>"));
+        };
+        Ok(Self {
+            name: HeaderName::from_str(name.trim()).map_err(|error| error.to_string())?,
+            value: HeaderValue::from_str(value.trim()).map_err(|error| error.to_string())?,
+        })
+    }
+}
+
+/// Options of the HTTP client the reports are sent with, common to all the receivers.
+#[derive(Debug, Default)]
+pub(crate) struct ReceiverOptions {
+    /// PEM bundle of the CA certificates trusted in addition to the built-in ones.
+    pub(crate) ca_bundle: Option<PathBuf>,
+    /// Proxy the requests are sent through.
+    pub(crate) proxy: Option<Url>,
+    /// Headers sent in addition to the call-home ones, which they override.
+    pub(crate) headers: Vec<ExtraHeader>,
+}
+
+impl ReceiverOptions {
+    /// Reads the CA certificates of the bundle, if any.
+    fn ca_certificates(&self) -> Result<Vec<Certificate>, ReceiverError> {
+        let Some(path) = &self.ca_bundle else {
+            return Ok(Vec::new());
+        };
+        let bundle =
+            fs::read_to_string(path).map_err(|source| ReceiverError::ReadCaBundleError {
+                source,
+                path: path.display().to_string(),
+            })?;
+        let certificates = bundle
+            .match_indices(PEM_CERTIFICATE_BEGIN)
+            .map(|(start, _)| start)
+            .collect::<Vec<_>>();
+        if certificates.is_empty() {
+            return Err(ReceiverError::EmptyCaBundleError {
+                path: path.display().to_string(),
+            });
+        }
+        certificates
+            .iter()
+            .enumerate()
+            .map(|(index, start)| {
+                let end = certificates.get(index + 1).copied().unwrap_or(bundle.len());
+                Certificate::from_pem(bundle[*start .. end].as_bytes()).map_err(ReceiverError::from)
+            })
+            .collect()
+    }
+}
+
+/// Struct used to make calls to a receiver API, the reports being sent to each receiver.
 pub struct Receiver {
     cluster_id: String,
     client: ClientWithMiddleware,
-    url: String,
+    url: Url,
+    headers: HeaderMap,
 }
 
 impl Receiver {
-    /// 'Receiver::new()' creates a new instance of Receiver
-    /// which is initialized with sane default values.
-    pub(crate) async fn new<T>(cluster_id: T) -> Result<Self, ReceiverError>
+    /// 'Receiver::new()' creates a new instance of Receiver for the url, which verifies its
+    /// certificate against the built-in and the configured CA certificates.
+    pub(crate) async fn new<T>(
+        cluster_id: T,
+        url: Url,
+        options: &ReceiverOptions,
+    ) -> Result<Self, ReceiverError>
     where
         T: ToString,
     {
         // Retry up to 3 times with increasing intervals between attempts.
         let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
 
-        let client_config = reqwest::Client::builder()
-            .danger_accept_invalid_certs(true)
-            .build()?;
+        let mut client_config = reqwest::Client::builder();
+        for certificate in options.ca_certificates()? {
+            client_config = client_config.add_root_certificate(certificate);
+        }
+        if let Some(proxy) = &options.proxy {
+            client_config = client_config.proxy(Proxy::all(proxy.clone())?);
+        }
+        let client_config = client_config.build()?;
+        let headers = options
+            .headers
+            .iter()
+            .map(|header| (header.name.clone(), header.value.clone()))
+            .collect();
         Ok(Self {
             cluster_id: cluster_id.to_string(),
             client: ClientBuilder::new(client_config)
                 .with(RetryTransientMiddleware::new_with_policy(retry_policy))
                 .build(),
-            url: RECEIVER_ENDPOINT.to_string(),
+            url,
+            headers,
         })
     }
 
+    /// 'url()' returns the url of the receiver API.
+    pub(crate) fn url(&self) -> &Url {
+        &self.url
+    }
+
     /// 'post()' method attempts an HTTP POST with some headers
     pub(crate) async fn post(&self, body: Vec<u8>) -> Result<Response, ReceiverError> {
         Ok(self
             .client
-            .post(&self.url)
+            .post(self.url.clone())
             .header("CAStor-Cluster-Id", &self.cluster_id)
             .header("CAStor-Version", release_version())
             .header("CAStor-Report-Type", "health_report")
             .header("CAStor-Product", product())
             .header("CAStor-Time", Utc::now().to_string())
             .header("Content-Type", "text/PGP; charset=binary")
+            .headers(self.headers.clone())
             .body(body)
             .send()
             .await?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ExtraHeader;
+
+    #[test]
+    fn test_extra_header() {
+        let header = "X-Fleet-Token: abc def".parse::<ExtraHeader>().unwrap();
+        assert_eq!(header.name, "x-fleet-token");
+        assert_eq!(header.value, "abc def");
+
+        assert!("X-Fleet-Token".parse::<ExtraHeader>().is_err());
+        assert!("X Fleet: abc".parse::<ExtraHeader>().is_err());
+    }
+}
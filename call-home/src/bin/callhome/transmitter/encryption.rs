@@ -1,48 +1,132 @@
 use crate::collector::report_models::Report;
-use obs::common::errors::EncryptError;
-use rand::{distributions::Alphanumeric, Rng};
-use std::{
-    fs,
-    path::{Path, PathBuf},
-    process::Command,
+use obs::common::{constants::ENCRYPTION_RECIPIENT, errors::EncryptError};
+use pgp::{
+    composed::{Deserializable, Message, SignedPublicKey},
+    crypto::sym::SymmetricKeyAlgorithm,
+    packet::Signature,
+    ser::Serialize,
+    types::{CompressionAlgorithm, KeyTrait, PublicKeyTrait},
 };
+use std::{fs::File, io::BufReader, path::Path};
 use tracing::debug;
 
-/// 'encrypt' accepts a crate::collector::Report, marshals it into JSON and encrypts it.
-pub(crate) fn encrypt(
-    report: &Report,
-    encryption_dir: &PathBuf,
-    key_filepath: &Path,
-) -> Result<Vec<u8>, EncryptError> {
-    // The underlying filesystem resource is garbage collected
-    // when this function returns.
-    let temp_file = tempfile::NamedTempFile::new_in(encryption_dir)?;
-    debug!("Successfully created temporary input file.");
-
-    let input_filepath = temp_file.into_temp_path();
+/// Name of the literal data packet holding the Report in the encrypted message.
+const REPORT_FILENAME: &str = "report.json";
+
+/// 'encrypt' accepts a crate::collector::Report, marshals it into JSON and encrypts it into an
+/// OpenPGP message for the call-home recipient, whose public key is read from the keyring at
+/// 'key_filepath'. The message is encrypted to the subkey of the recipient flagged for encryption,
+/// or else to its primary key when that is flagged for encryption.
+pub(crate) fn encrypt(report: &Report, key_filepath: &Path) -> Result<Vec<u8>, EncryptError> {
+    let key = recipient_key(key_filepath)?;
     let report_content = serde_json::to_vec(report)?;
-    fs::write(&input_filepath, report_content)?;
-    debug!("Successfully written Report data to temporary input file.");
-
-    let random_name: String = rand::thread_rng()
-        .sample_iter(&Alphanumeric)
-        .take(32)
-        .map(char::from)
-        .collect();
-    let output_filepath = Path::new(encryption_dir).join(random_name + ".gpg");
-
-    // TODO: Use a library instead of the gpg binary.
-    let command = format!("gpg --yes --trust-model=always --homedir={} --keyring={} --recipient=openebs-phonehome@datacore.com --no-default-keyring --encrypt -z=9 --output={} {}",
-                          encryption_dir.to_string_lossy(),
-                          key_filepath.to_string_lossy(),
-                          output_filepath.to_string_lossy(),
-                          input_filepath.to_string_lossy());
-    let _ = Command::new("sh").args(["-c", command.trim()]).output()?;
-    debug!("Successfully executed gpg command.");
-
-    let output = fs::read(&output_filepath)?;
-    fs::remove_file(&output_filepath)?;
-    debug!("Successfully deleted output file.");
-
-    Ok(output)
+    let subkey = key
+        .public_subkeys
+        .iter()
+        .find(|subkey| subkey.is_encryption_key() && encryption_flagged(subkey.signatures.iter()));
+    let message = match subkey {
+        Some(subkey) => encrypt_to_key(&report_content, subkey)?,
+        None if key.is_encryption_key() && encryption_flagged(primary_signatures(&key)) => {
+            encrypt_to_key(&report_content, &key)?
+        }
+        None => {
+            return Err(EncryptError::NoEncryptionKey {
+                recipient: ENCRYPTION_RECIPIENT.to_string(),
+            })
+        }
+    };
+    debug!("Successfully encrypted Report data.");
+
+    Ok(message.to_bytes()?)
+}
+
+/// Encrypts the content into an OpenPGP message for the public key.
+fn encrypt_to_key(content: &[u8], key: &impl PublicKeyTrait) -> Result<Message, EncryptError> {
+    debug!(key_id = ?key.key_id(), "Successfully read the recipient encryption key.");
+    let message = Message::new_literal_bytes(REPORT_FILENAME, content)
+        .compress(CompressionAlgorithm::ZLIB)?
+        .encrypt_to_keys(
+            &mut rand::thread_rng(),
+            SymmetricKeyAlgorithm::AES256,
+            &[key],
+        )?;
+    Ok(message)
+}
+
+/// Returns the self signatures of the primary key, which hold its key flags.
+fn primary_signatures(key: &SignedPublicKey) -> impl Iterator<Item = &Signature> {
+    key.details.direct_signatures.iter().chain(
+        key.details
+            .users
+            .iter()
+            .flat_map(|user| user.signatures.iter()),
+    )
+}
+
+/// Whether any of the signatures flags the key for encrypting communications or storage.
+fn encryption_flagged<'a>(mut signatures: impl Iterator<Item = &'a Signature>) -> bool {
+    signatures.any(|signature| {
+        let flags = signature.key_flags();
+        flags.encrypt_comms() || flags.encrypt_storage()
+    })
+}
+
+/// Reads the keyring at 'key_filepath' and returns the verified public key of the call-home
+/// recipient.
+fn recipient_key(key_filepath: &Path) -> Result<SignedPublicKey, EncryptError> {
+    let keyring = BufReader::new(File::open(key_filepath)?);
+    for key in SignedPublicKey::from_bytes_many(keyring) {
+        let key = key.map_err(|source| EncryptError::KeyringParseError { source })?;
+        if key
+            .details
+            .users
+            .iter()
+            .any(|user| String::from_utf8_lossy(user.id.id()).contains(ENCRYPTION_RECIPIENT))
+        {
+            key.verify()
+                .map_err(|source| EncryptError::InvalidKey { source })?;
+            return Ok(key);
+        }
+    }
+    Err(EncryptError::RecipientKeyNotFound {
+        recipient: ENCRYPTION_RECIPIENT.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encrypt;
+    use crate::collector::report_models::Report;
+    use pgp::composed::{Deserializable, Message, SignedSecretKey};
+    use std::{fs::File, path::Path};
+
+    /// Encrypts a report for the public key of the test keyring, and decrypts it with the secret
+    /// key of the keyring.
+    fn encrypt_decrypt(keyring: &str) {
+        let key_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/keys");
+        let report = Report {
+            product_name: "Mayastor".to_string(),
+            ..Default::default()
+        };
+        let output = encrypt(&report, &key_dir.join(format!("{keyring}-public.gpg"))).unwrap();
+        let plaintext = serde_json::to_vec(&report).unwrap();
+        assert!(!output
+            .windows(plaintext.len())
+            .any(|window| window == plaintext));
+
+        let secret_key = File::open(key_dir.join(format!("{keyring}-secret.gpg"))).unwrap();
+        let secret_key = SignedSecretKey::from_bytes(secret_key).unwrap();
+        let message = Message::from_bytes(output.as_slice()).unwrap();
+        let (mut decrypter, _) = message.decrypt(String::new, &[&secret_key]).unwrap();
+        let decrypted = decrypter.next().unwrap().unwrap().decompress().unwrap();
+        assert_eq!(decrypted.get_content().unwrap(), Some(plaintext));
+    }
+
+    #[test]
+    fn test_encrypt() {
+        // The recipient key has an encryption subkey.
+        encrypt_decrypt("subkey");
+        // The recipient key has no subkey, but its primary key is flagged for encryption.
+        encrypt_decrypt("primary");
+    }
 }
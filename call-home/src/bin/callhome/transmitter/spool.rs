@@ -0,0 +1,291 @@
+use crate::collector::report_models::Report;
+use chrono::Utc;
+use k8s_openapi::{api::core::v1::Secret, ByteString};
+use kube::{
+    api::{Api, Patch, PatchParams},
+    core::ObjectMeta,
+};
+use obs::common::{constants::SPOOL_FIELD_MANAGER, errors::SpoolError};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::debug;
+
+/// Prefix of the names of the spooled reports, followed by the time they were generated.
+const REPORT_PREFIX: &str = "report-";
+
+/// Format of the time the spooled reports were generated, down to the microsecond for the
+/// names to be unique and to sort in the order the reports were generated.
+const REPORT_TIME_FORMAT: &str = "%Y%m%dT%H%M%S%.6fZ";
+
+/// Extension of the spooled plaintext reports.
+const PLAINTEXT_EXTENSION: &str = ".json";
+
+/// Extension of the spooled encrypted reports.
+const ENCRYPTED_EXTENSION: &str = ".json.gpg";
+
+/// Forms in which each report is written to the spool.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SpoolFormat {
+    /// The report as pretty JSON.
+    Plaintext,
+    /// The report encrypted for the call-home recipient, as sent to the receiver.
+    Encrypted,
+    /// Both the plaintext and the encrypted report.
+    Both,
+}
+
+impl SpoolFormat {
+    /// Whether the encrypted report is written to the spool.
+    pub(crate) fn encrypted(&self) -> bool {
+        matches!(self, Self::Encrypted | Self::Both)
+    }
+
+    /// Whether the plaintext report is written to the spool.
+    fn plaintext(&self) -> bool {
+        matches!(self, Self::Plaintext | Self::Both)
+    }
+}
+
+/// Where the spooled reports are written.
+pub(crate) enum SpoolTarget {
+    /// A file per report in the directory.
+    Directory(PathBuf),
+    /// A key per report in the Secret, which is created if it does not exist.
+    Secret { api: Api<Secret>, name: String },
+}
+
+/// Spool the generated reports are written to, for a collector of their own to pick them up
+/// where the receiver cannot be reached. Only the most recent reports are retained, within both
+/// a count and a total size.
+pub(crate) struct Spool {
+    target: SpoolTarget,
+    format: SpoolFormat,
+    retention: usize,
+    max_size: u64,
+}
+
+impl Spool {
+    /// 'Spool::new()' creates a new instance of Spool, which retains the 'retention' most recent
+    /// reports, as long as their entries add up to no more than 'max_size' bytes.
+    pub(crate) fn new(
+        target: SpoolTarget,
+        format: SpoolFormat,
+        retention: usize,
+        max_size: u64,
+    ) -> Self {
+        Self {
+            target,
+            format,
+            retention,
+            max_size,
+        }
+    }
+
+    /// Forms in which each report is written to the spool.
+    pub(crate) fn format(&self) -> SpoolFormat {
+        self.format
+    }
+
+    /// 'write()' writes the report to the spool in the configured forms, then drops the oldest
+    /// reports beyond the retention count or size. 'encrypted' is the encrypted report, required if
+    /// the encrypted form is spooled.
+    pub(crate) async fn write(
+        &self,
+        report: &Report,
+        encrypted: Option<&[u8]>,
+    ) -> Result<(), SpoolError> {
+        let name = format!("{REPORT_PREFIX}{}", Utc::now().format(REPORT_TIME_FORMAT));
+        let mut entries = Vec::new();
+        if self.format.plaintext() {
+            entries.push((
+                format!("{name}{PLAINTEXT_EXTENSION}"),
+                serde_json::to_vec_pretty(report)?,
+            ));
+        }
+        if let (true, Some(encrypted)) = (self.format.encrypted(), encrypted) {
+            entries.push((format!("{name}{ENCRYPTED_EXTENSION}"), encrypted.to_vec()));
+        }
+
+        match &self.target {
+            SpoolTarget::Directory(dir) => self.write_directory(dir, entries),
+            SpoolTarget::Secret { api, name: secret } => {
+                self.write_secret(api, secret, entries).await
+            }
+        }?;
+        debug!(report = %name, "Successfully written Report to the spool.");
+        Ok(())
+    }
+
+    /// Writes the entries as files of the directory, and removes the files of the reports beyond
+    /// the retention count or size.
+    fn write_directory(
+        &self,
+        dir: &Path,
+        entries: Vec<(String, Vec<u8>)>,
+    ) -> Result<(), SpoolError> {
+        for (file_name, content) in entries {
+            // Write to a hidden file first, so that a partial report is never picked up.
+            let partial_path = dir.join(format!(".{file_name}.partial"));
+            fs::write(&partial_path, content)?;
+            fs::rename(partial_path, dir.join(file_name))?;
+        }
+
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if let Some(file_name) = entry.file_name().to_str() {
+                files.push((file_name.to_string(), entry.metadata()?.len()));
+            }
+        }
+        let files = files.iter().map(|(file_name, size)| (file_name, *size));
+        for file_name in self.expired(files) {
+            fs::remove_file(dir.join(file_name))?;
+        }
+        Ok(())
+    }
+
+    /// Adds the entries to the data of the Secret, without the reports beyond the retention
+    /// count or size, the size of a Secret being limited to 1MiB.
+    async fn write_secret(
+        &self,
+        api: &Api<Secret>,
+        name: &str,
+        entries: Vec<(String, Vec<u8>)>,
+    ) -> Result<(), SpoolError> {
+        let secret_error = |source| SpoolError::SecretError {
+            source,
+            name: name.to_string(),
+        };
+        let mut data = api
+            .get_opt(name)
+            .await
+            .map_err(secret_error)?
+            .and_then(|secret| secret.data)
+            .unwrap_or_default();
+        data.extend(
+            entries
+                .into_iter()
+                .map(|(key, content)| (key, ByteString(content))),
+        );
+        let expired = self
+            .expired(
+                data.iter()
+                    .map(|(key, content)| (key, content.0.len() as u64)),
+            )
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        for key in expired {
+            data.remove(&key);
+        }
+
+        let secret = Secret {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                ..Default::default()
+            },
+            data: Some(data),
+            ..Default::default()
+        };
+        // Applying the whole data also removes the keys of the expired reports.
+        let params = PatchParams::apply(SPOOL_FIELD_MANAGER).force();
+        api.patch(name, &params, &Patch::Apply(&secret))
+            .await
+            .map_err(secret_error)?;
+        Ok(())
+    }
+
+    /// Returns the names of the spooled reports beyond the retention count or size, out of the
+    /// names and sizes of the spool entries. The reports are named after the time they were
+    /// generated, so the oldest ones sort first. The most recent report is always retained.
+    fn expired<'a>(&self, entries: impl Iterator<Item = (&'a String, u64)>) -> Vec<&'a String> {
+        let names = entries
+            .filter(|(name, _)| name.starts_with(REPORT_PREFIX))
+            .filter_map(|(name, size)| {
+                let report = name
+                    .strip_suffix(ENCRYPTED_EXTENSION)
+                    .or_else(|| name.strip_suffix(PLAINTEXT_EXTENSION))?;
+                Some((report, name, size))
+            })
+            .collect::<Vec<_>>();
+        let mut reports = BTreeMap::<&str, u64>::new();
+        for (report, _, size) in &names {
+            *reports.entry(report).or_default() += size;
+        }
+        let mut retained_size = 0u64;
+        let expired = reports
+            .iter()
+            .rev()
+            .enumerate()
+            .filter(|(index, (_, size))| {
+                retained_size = retained_size.saturating_add(**size);
+                *index > 0 && (*index >= self.retention || retained_size > self.max_size)
+            })
+            .map(|(_, (report, _))| *report)
+            .collect::<BTreeSet<_>>();
+        names
+            .into_iter()
+            .filter(|(report, _, _)| expired.contains(report))
+            .map(|(_, name, _)| name)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Spool, SpoolFormat, SpoolTarget};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_expired() {
+        let spool = Spool::new(
+            SpoolTarget::Directory(PathBuf::new()),
+            SpoolFormat::Both,
+            2,
+            1024,
+        );
+        let names = [
+            "report-20231002T000000.000000Z.json",
+            "report-20231002T000000.000000Z.json.gpg",
+            "report-20231001T000000.000000Z.json",
+            "report-20231001T000000.000000Z.json.gpg",
+            "report-20231003T000000.000000Z.json.gpg",
+            ".report-20231004T000000.000000Z.json.partial",
+            "key",
+        ]
+        .map(String::from);
+        let mut expired = spool.expired(names.iter().map(|name| (name, 100)));
+        expired.sort();
+        assert_eq!(
+            expired,
+            vec![
+                "report-20231001T000000.000000Z.json",
+                "report-20231001T000000.000000Z.json.gpg"
+            ]
+        );
+
+        // Beyond the size, the older reports expire even within the retention count, but the
+        // most recent one is always retained.
+        let mut expired = spool.expired(names.iter().map(|name| (name, 600)));
+        expired.sort();
+        assert_eq!(
+            expired,
+            vec![
+                "report-20231001T000000.000000Z.json",
+                "report-20231001T000000.000000Z.json.gpg",
+                "report-20231002T000000.000000Z.json",
+                "report-20231002T000000.000000Z.json.gpg",
+            ]
+        );
+        let spool = Spool::new(
+            SpoolTarget::Directory(PathBuf::new()),
+            SpoolFormat::Both,
+            2,
+            0,
+        );
+        assert_eq!(spool.expired(names.iter().map(|name| (name, 600))).len(), 4);
+    }
+}
@@ -68,6 +68,18 @@ pub const NEXUS: &str = "nexus";
 /// Field manager for Patch param, required for [`Patch::Apply`].
 pub const PATCH_PARAM_FILED_MANAGER: &str = "events_store_configmap";
 
+/// Field manager for Patch param of the call-home spool secret.
+pub const SPOOL_FIELD_MANAGER: &str = "callhome_spool";
+
+/// Field manager for Patch param of the call-home state config map.
+pub const SCHEDULE_FIELD_MANAGER: &str = "callhome_schedule";
+
+/// Defines the default name of the config map the call-home state is persisted to.
+pub const DEFAULT_STATE_CONFIG_MAP: &str = "obs-callhome-state";
+
+/// Defines the key of the time of the last call-home report in the state config map.
+pub const LAST_SENT_KEY: &str = "lastSent";
+
 /// Default mbus url.
 pub const DEFAULT_MBUS_URL: &str = "nats://mayastor-nats:4222";
 
@@ -77,29 +89,6 @@ pub const DEFAULT_NAMESPACE: &str = "mayastor";
 /// Number of bytes in a disk sector.
 pub const BYTES_PER_SECTOR: u64 = 512;
 
-/// DEFAULT_ENCRYPTION_DIR_PATH is the directory path for the temporary files generated during
-/// encryption.
-/// The function encryption_dir() returns the user defined directory path for the encryption
-/// dir as an &str.
-const DEFAULT_ENCRYPTION_DIR_PATH: &str = "./";
-
-pub fn encryption_dir() -> PathBuf {
-    const KEY: &str = "ENCRYPTION_DIR";
-    match env::var(KEY) {
-        Ok(input) => {
-            let path = Path::new(&input);
-            // Validate path.
-            match path.exists() && path.is_dir() {
-                true => path.to_path_buf(),
-                false => {
-                    panic!("validation failed for {} value \"{}\": path must exist and must be that of a directory", KEY, &input);
-                }
-            }
-        }
-        Err(_) => Path::new(DEFAULT_ENCRYPTION_DIR_PATH).to_path_buf(),
-    }
-}
-
 /// DEFAULT_ENCRYPTION_KEY_FILEPATH is the path to the encryption key.
 /// The function key_filepath() returns the user defined path for the encryption key.
 const DEFAULT_ENCRYPTION_KEY_FILEPATH: &str = "./public.gpg";
@@ -121,22 +110,14 @@ pub fn key_filepath() -> PathBuf {
     }
 }
 
+/// ENCRYPTION_RECIPIENT is the user id of the key in the keyring the reports are encrypted for.
+pub const ENCRYPTION_RECIPIENT: &str = "openebs-phonehome@datacore.com";
+
 /// RECEIVER_API_ENDPOINT is the URL to anonymous call-home metrics collection endpoint.
 pub const RECEIVER_ENDPOINT: &str = ::constants::CALL_HOME_ENDPOINT;
 
-/// CALL_HOME_FREQUENCY_IN_HOURS is the frequency of call-home metrics transmission, in hours.
-/// The function call_home_frequency() returns the frequency as an std::time::Duration.
-
-const CALL_HOME_FREQUENCY_IN_HOURS: i64 = 24;
-
-pub fn call_home_frequency() -> std::time::Duration {
-    chrono::Duration::hours(CALL_HOME_FREQUENCY_IN_HOURS)
-        .to_std()
-        .map_err(|error| {
-            anyhow::anyhow!("failed to parse call-home frequency duration: {:?}", error)
-        })
-        .unwrap()
-}
+/// DEFAULT_REPORT_INTERVAL is the default interval of call-home metrics transmission.
+pub const DEFAULT_REPORT_INTERVAL: &str = "24h";
 
 /// Returns the git tag version (if tag is found) or simply returns the commit hash (12 characters).
 pub fn release_version() -> String {
@@ -35,6 +35,12 @@ pub enum ReceiverError {
 
     #[snafu(display("HTTP client (with middleware) error: {}", source))]
     HttpClientWithMiddlewareError { source: reqwest_middleware::Error },
+
+    #[snafu(display("failed to read the CA bundle {}: {}", path, source))]
+    ReadCaBundleError { source: std::io::Error, path: String },
+
+    #[snafu(display("no certificate found in the CA bundle {}", path))]
+    EmptyCaBundleError { path: String },
 }
 
 impl From<reqwest::Error> for ReceiverError {
@@ -60,6 +66,21 @@ pub enum EncryptError {
 
     #[snafu(display("file io error: {}", source))]
     IoError { source: std::io::Error },
+
+    #[snafu(display("failed to parse the encryption keyring: {}", source))]
+    KeyringParseError { source: pgp::errors::Error },
+
+    #[snafu(display("invalid encryption key: {}", source))]
+    InvalidKey { source: pgp::errors::Error },
+
+    #[snafu(display("no key found for recipient {} in the encryption keyring", recipient))]
+    RecipientKeyNotFound { recipient: String },
+
+    #[snafu(display("no encryption subkey or primary key found for recipient {}", recipient))]
+    NoEncryptionKey { recipient: String },
+
+    #[snafu(display("OpenPGP encryption error: {}", source))]
+    PgpError { source: pgp::errors::Error },
 }
 
 impl From<serde_json::Error> for EncryptError {
@@ -74,6 +95,40 @@ impl From<std::io::Error> for EncryptError {
     }
 }
 
+impl From<pgp::errors::Error> for EncryptError {
+    fn from(source: pgp::errors::Error) -> Self {
+        Self::PgpError { source }
+    }
+}
+
+/// SpoolError is a custom error enum which is returned when writing a report to the
+/// crate::transmitter::spool::Spool.
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub), context(suffix(false)))]
+#[allow(clippy::enum_variant_names)]
+pub enum SpoolError {
+    #[snafu(display("error during JSON marshalling: {}", source))]
+    SerdeSerializeError { source: serde_json::Error },
+
+    #[snafu(display("spool directory io error: {}", source))]
+    IoError { source: std::io::Error },
+
+    #[snafu(display("failed to write to the spool secret {}: {}", name, source))]
+    SecretError { source: kube::Error, name: String },
+}
+
+impl From<serde_json::Error> for SpoolError {
+    fn from(source: serde_json::Error) -> Self {
+        Self::SerdeSerializeError { source }
+    }
+}
+
+impl From<std::io::Error> for SpoolError {
+    fn from(source: std::io::Error) -> Self {
+        Self::IoError { source }
+    }
+}
+
 /// A wrapper type to remove repeated Result<T, Error> returns.
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -342,4 +342,72 @@ Renders init containers. If unset it sets the container image.
         {{- $containers = append $containers $container }}
     {{- end -}}
     {{- tpl ($containers | toYaml) .context }}
-{{- end -}}
\ No newline at end of file
+{{- end -}}
+{{/*
+Renders the metrics exporter arguments of the TLS and bearer token options of the metrics endpoint, if any
+Usage:
+{{- with include "metrics_exporter_security_args" . }}{{ . | nindent 10 }}{{ end }}
+*/}}
+{{- define "metrics_exporter_security_args" -}}
+    {{- $args := list }}
+    {{- with .Values.base.metrics }}
+        {{- if .tls.secretName }}
+            {{- $args = append $args "--tls-cert-file=/etc/metrics-exporter/tls/tls.crt" }}
+            {{- $args = append $args "--tls-key-file=/etc/metrics-exporter/tls/tls.key" }}
+            {{- if .tls.clientCaKey }}
+                {{- $args = append $args (printf "--tls-client-ca-file=/etc/metrics-exporter/tls/%s" .tls.clientCaKey) }}
+            {{- end }}
+        {{- end }}
+        {{- if .auth.tokenSecretName }}
+            {{- $args = append $args "--auth-token-file=/etc/metrics-exporter/auth/tokens" }}
+        {{- end }}
+        {{- if .auth.tokenReview }}
+            {{- $args = append $args "--auth-token-review" }}
+        {{- end }}
+    {{- end }}
+    {{- if $args }}
+        {{- toYaml $args }}
+    {{- end }}
+{{- end -}}
+
+{{/*
+Renders the metrics exporter volume mounts of the TLS certificate and bearer tokens Secrets, if any
+Usage:
+{{- with include "metrics_exporter_security_volume_mounts" . }}
+volumeMounts: {{- . | nindent 8 }}
+{{- end }}
+*/}}
+{{- define "metrics_exporter_security_volume_mounts" -}}
+    {{- $mounts := list }}
+    {{- with .Values.base.metrics }}
+        {{- if .tls.secretName }}
+            {{- $mounts = append $mounts (dict "name" "metrics-exporter-tls" "mountPath" "/etc/metrics-exporter/tls" "readOnly" true) }}
+        {{- end }}
+        {{- if .auth.tokenSecretName }}
+            {{- $mounts = append $mounts (dict "name" "metrics-exporter-auth" "mountPath" "/etc/metrics-exporter/auth" "readOnly" true) }}
+        {{- end }}
+    {{- end }}
+    {{- if $mounts }}
+        {{- toYaml $mounts }}
+    {{- end }}
+{{- end -}}
+
+{{/*
+Renders the metrics exporter volumes of the TLS certificate and bearer tokens Secrets, if any
+Usage:
+{{- with include "metrics_exporter_security_volumes" . }}{{ . | nindent 6 }}{{ end }}
+*/}}
+{{- define "metrics_exporter_security_volumes" -}}
+    {{- $volumes := list }}
+    {{- with .Values.base.metrics }}
+        {{- if .tls.secretName }}
+            {{- $volumes = append $volumes (dict "name" "metrics-exporter-tls" "secret" (dict "secretName" .tls.secretName)) }}
+        {{- end }}
+        {{- if .auth.tokenSecretName }}
+            {{- $volumes = append $volumes (dict "name" "metrics-exporter-auth" "secret" (dict "secretName" .auth.tokenSecretName)) }}
+        {{- end }}
+    {{- end }}
+    {{- if $volumes }}
+        {{- toYaml $volumes }}
+    {{- end }}
+{{- end -}}
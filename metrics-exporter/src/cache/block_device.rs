@@ -0,0 +1,57 @@
+use super::{Cache, ResourceOps, ResourceType};
+use crate::client::{
+    block_device::{BlockDeviceInfo, BlockDevices},
+    IoEngineClient,
+};
+use std::sync::Mutex;
+use tracing::error;
+
+impl ResourceOps for BlockDevices {
+    type ResourceVec = Vec<BlockDeviceInfo>;
+
+    fn set(&mut self, val: Self::ResourceVec) {
+        self.devices = val
+    }
+
+    fn invalidate(&mut self) {
+        self.devices = vec![]
+    }
+}
+
+/// To store the block devices of the node in cache.
+pub(crate) async fn store_block_device_data(
+    client: &dyn IoEngineClient,
+    cache: &Mutex<Cache>,
+    node: &str,
+) -> Result<(), ()> {
+    let devices = client.list_block_devices().await;
+    let mut cache = match cache.lock() {
+        Ok(cache) => cache,
+        Err(error) => {
+            error!(%error, "Error while getting cache resource");
+            return Err(());
+        }
+    };
+    let device_cache = cache.node_mut(node);
+    match devices {
+        Ok(devices) => {
+            device_cache.block_device_mut().set(devices.devices);
+            device_cache
+                .exporter_stats_mut()
+                .record_success(ResourceType::BlockDevice);
+        }
+        // invalidate cache in case of error
+        Err(error) => {
+            error!(
+                ?error,
+                "Error getting block device data, invalidating block device cache"
+            );
+            device_cache.block_device_mut().invalidate();
+            device_cache
+                .exporter_stats_mut()
+                .record_failure(ResourceType::BlockDevice);
+            return Err(());
+        }
+    };
+    Ok(())
+}
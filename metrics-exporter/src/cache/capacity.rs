@@ -0,0 +1,121 @@
+use crate::client::pool::PoolInfo;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::{Duration, SystemTime},
+};
+
+/// Minimum number of samples required to forecast the capacity of a pool.
+const MIN_FORECAST_SAMPLES: usize = 3;
+
+/// Sample of the used capacity of a pool.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+struct CapacitySample {
+    time: SystemTime,
+    used: u64,
+}
+
+/// Forecast of the capacity of a pool based on the trend of its used capacity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct CapacityForecast {
+    /// Growth of the used capacity in bytes per second.
+    pub(crate) growth_bytes_per_second: f64,
+    /// Seconds until the pool is full at the current growth, if it is growing.
+    pub(crate) seconds_until_full: Option<f64>,
+}
+
+/// Sliding window of the used capacity samples of all pools, by pool name.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct PoolCapacityHistory {
+    samples: HashMap<String, VecDeque<CapacitySample>>,
+}
+
+impl PoolCapacityHistory {
+    /// Add the new samples, dropping the samples older than the window and the pools which no
+    /// longer exist.
+    pub(crate) fn update(&mut self, pools: &[PoolInfo], window: Duration) {
+        let now = SystemTime::now();
+        let names = pools.iter().map(|pool| pool.name()).collect::<HashSet<_>>();
+        self.samples.retain(|name, _| names.contains(name));
+        for pool in pools {
+            let samples = self.samples.entry(pool.name().clone()).or_default();
+            samples.push_back(CapacitySample {
+                time: now,
+                used: pool.used(),
+            });
+            while samples
+                .front()
+                .is_some_and(|sample| now.duration_since(sample.time).unwrap_or_default() > window)
+            {
+                samples.pop_front();
+            }
+        }
+    }
+
+    /// Forecast the capacity of the pool from its samples.
+    pub(crate) fn forecast(&self, pool: &PoolInfo) -> Option<CapacityForecast> {
+        let samples = self.samples.get(pool.name())?;
+        if samples.len() < MIN_FORECAST_SAMPLES {
+            return None;
+        }
+        let start = samples.front()?.time;
+        let points = samples
+            .iter()
+            .map(|sample| {
+                let elapsed = sample.time.duration_since(start).unwrap_or_default();
+                (elapsed.as_secs_f64(), sample.used as f64)
+            })
+            .collect::<Vec<_>>();
+        let growth_bytes_per_second = linear_regression_slope(&points)?;
+        let free = pool.capacity().saturating_sub(pool.used()) as f64;
+        Some(CapacityForecast {
+            growth_bytes_per_second,
+            seconds_until_full: (growth_bytes_per_second > 0.0)
+                .then(|| free / growth_bytes_per_second),
+        })
+    }
+}
+
+/// Least squares slope of the line fitting the (x, y) points. None if the slope is not defined,
+/// i.e. if all the points have the same x.
+fn linear_regression_slope(points: &[(f64, f64)]) -> Option<f64> {
+    if points.is_empty() {
+        return None;
+    }
+    let count = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+    let (covariance, variance) =
+        points
+            .iter()
+            .fold((0.0, 0.0), |(covariance, variance), (x, y)| {
+                (
+                    covariance + (x - mean_x) * (y - mean_y),
+                    variance + (x - mean_x).powi(2),
+                )
+            });
+    (variance > 0.0).then(|| covariance / variance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::linear_regression_slope;
+
+    #[test]
+    fn test_linear_regression_slope() {
+        assert_eq!(linear_regression_slope(&[]), None);
+        assert_eq!(linear_regression_slope(&[(5.0, 1.0), (5.0, 2.0)]), None);
+        assert_eq!(
+            linear_regression_slope(&[(0.0, 10.0), (10.0, 30.0), (20.0, 50.0)]),
+            Some(2.0)
+        );
+        assert_eq!(
+            linear_regression_slope(&[(0.0, 0.0), (1.0, 2.0), (2.0, 1.0), (3.0, 3.0)]),
+            Some(0.8)
+        );
+        assert_eq!(
+            linear_regression_slope(&[(0.0, 50.0), (10.0, 50.0), (20.0, 50.0)]),
+            Some(0.0)
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use crate::client::IoStat;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    time::SystemTime,
+};
+
+/// Counter which stays monotonic when the io-engine counter it follows is reset, which happens
+/// whenever io-engine restarts.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub(crate) struct MonotonicCounter {
+    /// Last value reported by io-engine.
+    last: Option<u64>,
+    /// Sum of the values reported by io-engine before each reset.
+    offset: u64,
+    /// Increase of the counter with the last update.
+    increase: u64,
+    /// Last non-zero increase of the counter along with the time it was sampled at, exposed as
+    /// the exemplar of the counter.
+    exemplar: Option<(u64, SystemTime)>,
+}
+
+impl MonotonicCounter {
+    /// Update the counter with a new value reported by io-engine.
+    pub(crate) fn update(&mut self, value: u64) {
+        self.increase = match self.last {
+            None => 0,
+            Some(last) if value < last => {
+                self.offset = self.offset.saturating_add(last);
+                value
+            }
+            Some(last) => value - last,
+        };
+        self.last = Some(value);
+        if self.increase > 0 {
+            self.exemplar = Some((self.increase, SystemTime::now()));
+        }
+    }
+
+    /// Get the monotonic value of the counter.
+    pub(crate) fn value(&self) -> u64 {
+        self.offset.saturating_add(self.last.unwrap_or_default())
+    }
+
+    /// Get the increase of the counter with the last update.
+    pub(crate) fn increase(&self) -> u64 {
+        self.increase
+    }
+
+    /// Get the last non-zero increase of the counter along with the time it was sampled at.
+    pub(crate) fn exemplar(&self) -> Option<(u64, SystemTime)> {
+        self.exemplar
+    }
+
+    /// Add to the counter, for a counter summing the increases of other counters.
+    fn add(&mut self, increase: u64) {
+        self.update(self.value().saturating_add(increase));
+    }
+}
+
+/// Monotonic io counters of a resource.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct IoStatCounters {
+    bytes_read: MonotonicCounter,
+    num_read_ops: MonotonicCounter,
+    bytes_written: MonotonicCounter,
+    num_write_ops: MonotonicCounter,
+    read_latency_us: MonotonicCounter,
+    write_latency_us: MonotonicCounter,
+}
+
+impl IoStatCounters {
+    /// Update the counters with a new sample from io-engine.
+    fn update<T: IoStat>(&mut self, stat: &T) {
+        self.bytes_read.update(stat.bytes_read());
+        self.num_read_ops.update(stat.num_read_ops());
+        self.bytes_written.update(stat.bytes_written());
+        self.num_write_ops.update(stat.num_write_ops());
+        self.read_latency_us.update(stat.read_latency_us());
+        self.write_latency_us.update(stat.write_latency_us());
+    }
+
+    /// Get the counter of the total bytes read.
+    pub(crate) fn bytes_read(&self) -> &MonotonicCounter {
+        &self.bytes_read
+    }
+
+    /// Get the counter of the total number of read ops.
+    pub(crate) fn num_read_ops(&self) -> &MonotonicCounter {
+        &self.num_read_ops
+    }
+
+    /// Get the counter of the total bytes written.
+    pub(crate) fn bytes_written(&self) -> &MonotonicCounter {
+        &self.bytes_written
+    }
+
+    /// Get the counter of the total number of write ops.
+    pub(crate) fn num_write_ops(&self) -> &MonotonicCounter {
+        &self.num_write_ops
+    }
+
+    /// Get the counter of the total read latency in usec.
+    pub(crate) fn read_latency_us(&self) -> &MonotonicCounter {
+        &self.read_latency_us
+    }
+
+    /// Get the counter of the total write latency in usec.
+    pub(crate) fn write_latency_us(&self) -> &MonotonicCounter {
+        &self.write_latency_us
+    }
+
+    /// Get the bytes written during the last polling interval.
+    pub(crate) fn bytes_written_increase(&self) -> u64 {
+        self.bytes_written.increase()
+    }
+}
+
+/// Monotonic io counters of all resources of a type, by resource name.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct IoCounters {
+    counters: HashMap<String, IoStatCounters>,
+}
+
+impl IoCounters {
+    /// Update the counters with the new samples, dropping resources which no longer exist.
+    pub(crate) fn update<T: IoStat>(&mut self, stats: &[T]) {
+        let names = stats.iter().map(|stat| stat.name()).collect::<HashSet<_>>();
+        self.counters.retain(|name, _| names.contains(name));
+        for stat in stats {
+            self.counters
+                .entry(stat.name().clone())
+                .or_default()
+                .update(stat);
+        }
+    }
+
+    /// Get the io counters of the resource.
+    pub(crate) fn get(&self, name: &str) -> Option<&IoStatCounters> {
+        self.counters.get(name)
+    }
+}
+
+/// Monotonic io counters of all the replicas of a volume, which do not drop when a replica goes
+/// away as they sum the increases of the counters of the replicas.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct VolumeReplicaCounters {
+    /// Names of the replicas of the volume already added.
+    replicas: HashSet<String>,
+    bytes_read: MonotonicCounter,
+    bytes_written: MonotonicCounter,
+}
+
+impl VolumeReplicaCounters {
+    /// Add the io of the replica since its last update, or all of it for a new replica.
+    fn add(&mut self, replica: &str, counters: &IoStatCounters) {
+        let increase = |counter: &MonotonicCounter| match self.replicas.contains(replica) {
+            true => counter.increase(),
+            false => counter.value(),
+        };
+        let (bytes_read, bytes_written) = (
+            increase(&counters.bytes_read),
+            increase(&counters.bytes_written),
+        );
+        self.bytes_read.add(bytes_read);
+        self.bytes_written.add(bytes_written);
+        self.replicas.insert(replica.to_string());
+    }
+
+    /// Get the counter of the total bytes read from the replicas.
+    pub(crate) fn bytes_read(&self) -> &MonotonicCounter {
+        &self.bytes_read
+    }
+
+    /// Get the counter of the total bytes written to the replicas.
+    pub(crate) fn bytes_written(&self) -> &MonotonicCounter {
+        &self.bytes_written
+    }
+}
+
+/// Monotonic io counters of the replicas of all volumes across the nodes, by volume uuid.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct VolumesReplicaCounters {
+    counters: HashMap<String, VolumeReplicaCounters>,
+}
+
+impl VolumesReplicaCounters {
+    /// Add the io of a replica of the volume since its last update.
+    pub(crate) fn add(&mut self, volume: String, replica: &str, counters: &IoStatCounters) {
+        self.counters
+            .entry(volume)
+            .or_default()
+            .add(replica, counters);
+    }
+
+    /// Drop the volumes which no longer have any replica.
+    pub(crate) fn retain(&mut self, volumes: &HashSet<String>) {
+        self.counters.retain(|volume, _| volumes.contains(volume));
+    }
+
+    /// Get the io counters of the replicas of the volume.
+    pub(crate) fn get(&self, volume: &str) -> Option<&VolumeReplicaCounters> {
+        self.counters.get(volume)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MonotonicCounter;
+
+    #[test]
+    fn test_monotonic_counter() {
+        let mut counter = MonotonicCounter::default();
+        counter.update(10);
+        assert_eq!(counter.increase(), 0);
+        assert_eq!(counter.exemplar(), None);
+        counter.update(25);
+        assert_eq!(counter.value(), 25);
+        assert_eq!(counter.increase(), 15);
+        // io-engine restarted.
+        counter.update(5);
+        assert_eq!(counter.value(), 30);
+        assert_eq!(counter.increase(), 5);
+        counter.update(7);
+        assert_eq!(counter.value(), 32);
+        // Restarted again, before any io.
+        counter.update(0);
+        assert_eq!(counter.value(), 32);
+        assert_eq!(counter.increase(), 0);
+        // The exemplar is the last non-zero increase.
+        assert_eq!(counter.exemplar().map(|(increase, _)| increase), Some(2));
+    }
+}
@@ -0,0 +1,100 @@
+use super::{Cache, ResourceType};
+use crate::client::{connection::ConnectionStats, exchange::GrpcExchanges};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+use tracing::error;
+
+/// Number of gRPC requests made to io-engine for a resource type, by result.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+pub(crate) struct GrpcRequests {
+    success: u64,
+    failure: u64,
+}
+
+impl GrpcRequests {
+    /// Get the number of successful requests.
+    pub(crate) fn success(&self) -> u64 {
+        self.success
+    }
+
+    /// Get the number of failed requests.
+    pub(crate) fn failure(&self) -> u64 {
+        self.failure
+    }
+}
+
+/// This stores health statistics of the exporter itself.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub(crate) struct ExporterStats {
+    /// gRPC request counts per resource type.
+    grpc_requests: HashMap<ResourceType, GrpcRequests>,
+    /// Time of the last successful update of each resource type.
+    last_success: HashMap<ResourceType, SystemTime>,
+    /// Duration of the last collection of all resources from io-engine.
+    collect_duration: Option<Duration>,
+    /// Statistics of the gRPC connection to io-engine as of the last collection.
+    connection: ConnectionStats,
+}
+
+impl ExporterStats {
+    /// Record a successful fetch of the resource type, marking it as updated now.
+    pub(crate) fn record_success(&mut self, resource: ResourceType) {
+        self.grpc_requests.entry(resource).or_default().success += 1;
+        self.last_success.insert(resource, SystemTime::now());
+    }
+
+    /// Record a failed fetch of the resource type.
+    pub(crate) fn record_failure(&mut self, resource: ResourceType) {
+        self.grpc_requests.entry(resource).or_default().failure += 1;
+    }
+
+    /// Get the gRPC request counts of all resource types.
+    pub(crate) fn grpc_requests(&self) -> &HashMap<ResourceType, GrpcRequests> {
+        &self.grpc_requests
+    }
+
+    /// Get the time of the last successful update of all resource types.
+    pub(crate) fn last_success(&self) -> &HashMap<ResourceType, SystemTime> {
+        &self.last_success
+    }
+
+    /// Get the duration of the last collection from io-engine, if any.
+    pub(crate) fn collect_duration(&self) -> Option<Duration> {
+        self.collect_duration
+    }
+
+    /// Get the statistics of the gRPC connection to io-engine.
+    pub(crate) fn connection(&self) -> &ConnectionStats {
+        &self.connection
+    }
+}
+
+/// To store the duration of the last collection and the gRPC connection statistics of the node in
+/// cache.
+pub(crate) fn store_collect_stats(
+    cache: &Mutex<Cache>,
+    node: &str,
+    duration: Duration,
+    connection: ConnectionStats,
+) {
+    match cache.lock() {
+        Ok(mut cache) => {
+            let exporter_stats = cache.node_mut(node).exporter_stats_mut();
+            exporter_stats.collect_duration = Some(duration);
+            exporter_stats.connection = connection;
+        }
+        Err(error) => error!(%error, "Error while getting cache resource"),
+    }
+}
+
+/// To store the last gRPC exchanges of the node with io-engine in cache.
+pub(crate) fn store_grpc_exchanges(cache: &Mutex<Cache>, node: &str, exchanges: GrpcExchanges) {
+    match cache.lock() {
+        Ok(mut cache) => cache.node_mut(node).grpc_exchanges = exchanges,
+        Err(error) => error!(%error, "Error while getting cache resource"),
+    }
+}
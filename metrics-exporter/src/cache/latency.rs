@@ -0,0 +1,202 @@
+use crate::client::IoStat;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    time::SystemTime,
+};
+
+/// Upper bounds of the latency histogram buckets in seconds.
+pub(crate) const LATENCY_BUCKETS: [f64; 14] = [
+    0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5,
+];
+
+/// Number of microseconds in a second.
+const US_PER_SEC: f64 = 1_000_000.0;
+
+/// Latency histogram built from successive samples of the cumulative io counters.
+/// All the ops completed between two samples are observed with the average latency of that
+/// interval.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct LatencyHistogram {
+    /// Number of ops observed in each bucket, not cumulative.
+    buckets: Vec<u64>,
+    /// Total number of ops observed.
+    count: u64,
+    /// Total latency observed in seconds.
+    sum: f64,
+    /// Average latency per op in seconds during the last interval with io.
+    interval_avg: Option<f64>,
+    /// Last sample of the cumulative ops and latency in usec.
+    last_sample: Option<(u64, u64)>,
+    /// Last interval observed in each bucket, exposed as the exemplar of the bucket.
+    exemplars: Vec<Option<LatencyExemplar>>,
+}
+
+/// Interval between two samples observed in a latency histogram bucket.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct LatencyExemplar {
+    /// Average latency per op in seconds during the interval.
+    pub(crate) latency: f64,
+    /// Number of ops completed during the interval.
+    pub(crate) ops: u64,
+    /// Time the interval ended at.
+    pub(crate) time: SystemTime,
+}
+
+impl LatencyHistogram {
+    /// Update the histogram with a new sample of the cumulative ops and latency in usec.
+    pub(crate) fn update(&mut self, num_ops: u64, latency_us: u64) {
+        if let Some((last_ops, last_latency_us)) = self.last_sample {
+            // The counters are reset when io-engine restarts, in which case everything counted
+            // since the restart happened within this interval.
+            let (ops, latency_us) = if num_ops < last_ops || latency_us < last_latency_us {
+                (num_ops, latency_us)
+            } else {
+                (num_ops - last_ops, latency_us - last_latency_us)
+            };
+            if ops > 0 {
+                self.observe(ops, latency_us as f64 / US_PER_SEC);
+            }
+        }
+        self.last_sample = Some((num_ops, latency_us));
+    }
+
+    /// Observe ops whose latency adds up to the given seconds.
+    fn observe(&mut self, ops: u64, latency: f64) {
+        let avg = latency / ops as f64;
+        if self.buckets.len() != LATENCY_BUCKETS.len() {
+            self.buckets = vec![0; LATENCY_BUCKETS.len()];
+            self.exemplars = vec![None; LATENCY_BUCKETS.len()];
+        }
+        if let Some(index) = LATENCY_BUCKETS.iter().position(|bound| avg <= *bound) {
+            self.buckets[index] += ops;
+            self.exemplars[index] = Some(LatencyExemplar {
+                latency: avg,
+                ops,
+                time: SystemTime::now(),
+            });
+        }
+        self.count += ops;
+        self.sum += latency;
+        self.interval_avg = Some(avg);
+    }
+
+    /// Get the cumulative number of ops observed for each bucket of `LATENCY_BUCKETS`.
+    pub(crate) fn cumulative_buckets(&self) -> Vec<u64> {
+        LATENCY_BUCKETS
+            .iter()
+            .enumerate()
+            .scan(0, |total, (index, _)| {
+                *total += self.buckets.get(index).copied().unwrap_or_default();
+                Some(*total)
+            })
+            .collect()
+    }
+
+    /// Get the last interval observed in each bucket of `LATENCY_BUCKETS`, if any.
+    pub(crate) fn exemplars(&self) -> impl Iterator<Item = Option<&LatencyExemplar>> {
+        (0 .. LATENCY_BUCKETS.len()).map(|index| self.exemplars.get(index).and_then(Option::as_ref))
+    }
+
+    /// Get the total number of ops observed.
+    pub(crate) fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Get the total latency observed in seconds.
+    pub(crate) fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// Get the average latency per op in seconds during the last interval with io.
+    pub(crate) fn interval_avg(&self) -> Option<f64> {
+        self.interval_avg
+    }
+}
+
+/// Read and write latency histograms of a resource.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct IoLatency {
+    read: LatencyHistogram,
+    write: LatencyHistogram,
+}
+
+impl IoLatency {
+    /// Get the read latency histogram.
+    pub(crate) fn read(&self) -> &LatencyHistogram {
+        &self.read
+    }
+
+    /// Get the write latency histogram.
+    pub(crate) fn write(&self) -> &LatencyHistogram {
+        &self.write
+    }
+}
+
+/// Latency histograms of all resources of a type, by resource name.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct IoLatencies {
+    latencies: HashMap<String, IoLatency>,
+}
+
+impl IoLatencies {
+    /// Update the histograms with the new samples, dropping resources which no longer exist.
+    pub(crate) fn update<T: IoStat>(&mut self, stats: &[T]) {
+        let names = stats.iter().map(|stat| stat.name()).collect::<HashSet<_>>();
+        self.latencies.retain(|name, _| names.contains(name));
+        for stat in stats {
+            let latency = self.latencies.entry(stat.name().clone()).or_default();
+            latency
+                .read
+                .update(stat.num_read_ops(), stat.read_latency_us());
+            latency
+                .write
+                .update(stat.num_write_ops(), stat.write_latency_us());
+        }
+    }
+
+    /// Get the latency histograms of the resource.
+    pub(crate) fn get(&self, name: &str) -> Option<&IoLatency> {
+        self.latencies.get(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LatencyHistogram, LATENCY_BUCKETS};
+
+    #[test]
+    fn test_latency_histogram() {
+        let mut histogram = LatencyHistogram::default();
+        // The first sample only sets the baseline.
+        histogram.update(100, 100_000);
+        assert_eq!(histogram.count(), 0);
+        assert_eq!(histogram.interval_avg(), None);
+
+        // 10 ops taking 20ms in total, i.e. 2ms each.
+        histogram.update(110, 120_000);
+        assert_eq!(histogram.count(), 10);
+        assert_eq!(histogram.sum(), 0.02);
+        assert_eq!(histogram.interval_avg(), Some(0.002));
+        let buckets = histogram.cumulative_buckets();
+        assert_eq!(buckets.len(), LATENCY_BUCKETS.len());
+        assert_eq!(buckets[3], 0);
+        assert_eq!(buckets[4], 10);
+        assert_eq!(buckets[LATENCY_BUCKETS.len() - 1], 10);
+        let exemplars = histogram.exemplars().collect::<Vec<_>>();
+        assert_eq!(exemplars.len(), LATENCY_BUCKETS.len());
+        assert!(exemplars[3].is_none());
+        assert_eq!(exemplars[4].map(|exemplar| exemplar.ops), Some(10));
+
+        // No io during the interval keeps the last average.
+        histogram.update(110, 120_000);
+        assert_eq!(histogram.count(), 10);
+        assert_eq!(histogram.interval_avg(), Some(0.002));
+
+        // Counters reset after an io-engine restart, 5 ops of 100us each.
+        histogram.update(5, 500);
+        assert_eq!(histogram.count(), 15);
+        assert_eq!(histogram.interval_avg(), Some(0.0001));
+        assert_eq!(histogram.cumulative_buckets()[0], 5);
+    }
+}
@@ -0,0 +1,378 @@
+mod block_device;
+mod capacity;
+mod counter;
+mod exporter;
+mod latency;
+mod nexus;
+mod nexus_stat;
+mod pool;
+mod pool_stat;
+mod qos;
+mod replica_stat;
+
+pub(crate) use capacity::PoolCapacityHistory;
+pub(crate) use counter::{IoCounters, MonotonicCounter, VolumesReplicaCounters};
+pub(crate) use exporter::ExporterStats;
+pub(crate) use latency::{IoLatencies, LatencyHistogram, LATENCY_BUCKETS};
+pub(crate) use qos::VolumesQos;
+
+use crate::{
+    client::{
+        block_device::BlockDevices, exchange::GrpcExchanges, nexus::Nexuses,
+        nexus_stat::NexusIoStats, pool::Pools, pool_stat::PoolIoStats,
+        replica_stat::ReplicaIoStats, IoEngineClient,
+    },
+    config::ExporterConfig,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::time::sleep;
+
+/// Cache shared between the task refreshing it and the collectors reading it.
+pub(crate) type SharedCache = Arc<Mutex<Cache>>;
+
+/// Trait to be implemented by all Resource structs stored in Cache.
+trait ResourceOps {
+    type ResourceVec;
+    fn set(&mut self, val: Self::ResourceVec);
+    fn invalidate(&mut self);
+}
+
+/// Type of the resources stored in cache.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, strum_macros::AsRefStr,
+)]
+#[strum(serialize_all = "snake_case")]
+pub(crate) enum ResourceType {
+    Pool,
+    BlockDevice,
+    Nexus,
+    PoolIoStat,
+    NexusIoStat,
+    ReplicaIoStat,
+}
+
+/// Cache to store data that has to be exposed though metrics-exporter, for each io-engine node.
+#[derive(Debug, Default)]
+pub(crate) struct Cache {
+    nodes: BTreeMap<String, Data>,
+    /// Contains the io counters of the replicas of the volumes across the nodes.
+    volume_replica_counters: VolumesReplicaCounters,
+}
+
+/// Wrapper over all the data of a node that has to be stored in cache.
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct Data {
+    /// Contains Pool Capacity and state data.
+    pools: Pools,
+    /// Contains the sliding window of Pool capacity samples.
+    pool_capacity_history: PoolCapacityHistory,
+    /// Contains the block devices of the node.
+    block_devices: BlockDevices,
+    /// Contains Nexus and rebuild data.
+    nexuses: Nexuses,
+    /// Contains Pool IOStats data.
+    pool_stats: PoolIoStats,
+    /// Contains Nexus IOStats data.
+    nexus_stats: NexusIoStats,
+    /// Contains Replica IOStats data.
+    replica_stats: ReplicaIoStats,
+    /// Contains Pool monotonic io counters.
+    pool_counters: IoCounters,
+    /// Contains Nexus monotonic io counters.
+    nexus_counters: IoCounters,
+    /// Contains Replica monotonic io counters.
+    replica_counters: IoCounters,
+    /// Contains the io rates of the volumes, derived from the Nexus IOStats.
+    volumes_qos: VolumesQos,
+    /// Contains Pool latency histograms.
+    pool_latency: IoLatencies,
+    /// Contains Nexus latency histograms.
+    nexus_latency: IoLatencies,
+    /// Contains Replica latency histograms.
+    replica_latency: IoLatencies,
+    /// Contains health statistics of the exporter itself.
+    exporter_stats: ExporterStats,
+    /// Contains the last gRPC exchanges with io-engine, served on their own debug endpoint.
+    #[serde(skip)]
+    grpc_exchanges: GrpcExchanges,
+}
+
+impl Cache {
+    /// Create a cache without any node, to be shared with the collectors.
+    pub(crate) fn new_shared() -> SharedCache {
+        Arc::new(Mutex::new(Self::default()))
+    }
+
+    /// Get the data of all the nodes, by node name.
+    pub(crate) fn nodes(&self) -> impl Iterator<Item = (&String, &Data)> {
+        self.nodes.iter()
+    }
+
+    /// Get mutable reference to the data of the node, adding the node if it is not cached yet.
+    pub(crate) fn node_mut(&mut self, node: &str) -> &mut Data {
+        self.nodes.entry(node.to_string()).or_default()
+    }
+
+    /// Drop the data of the nodes which are not in the given nodes.
+    pub(crate) fn retain_nodes(&mut self, nodes: &[String]) {
+        self.nodes.retain(|node, _| nodes.contains(node));
+    }
+
+    /// Get the io counters of the replicas of the volumes across the nodes.
+    pub(crate) fn volume_replica_counters(&self) -> &VolumesReplicaCounters {
+        &self.volume_replica_counters
+    }
+
+    /// Add the io of the replicas of the node since their last update to the counters of their
+    /// volumes, dropping the volumes without any replica left on the cached nodes.
+    pub(crate) fn update_volume_replica_counters(&mut self, node: &str) {
+        let volumes = self
+            .nodes
+            .values()
+            .flat_map(|data| &data.replica_stats.replica_stats)
+            .map(|replica_stat| replica_stat.entity_id())
+            .collect::<HashSet<_>>();
+        self.volume_replica_counters.retain(&volumes);
+        let Some(data) = self.nodes.get(node) else {
+            return;
+        };
+        for replica_stat in &data.replica_stats.replica_stats {
+            if let Some(counters) = data.replica_counters.get(replica_stat.name()) {
+                self.volume_replica_counters.add(
+                    replica_stat.entity_id(),
+                    replica_stat.name(),
+                    counters,
+                );
+            }
+        }
+    }
+}
+
+impl Data {
+    /// Constructor for Cache data of a node.
+    fn new() -> Self {
+        Self {
+            pools: Pools { pools: vec![] },
+            pool_capacity_history: PoolCapacityHistory::default(),
+            block_devices: BlockDevices { devices: vec![] },
+            nexuses: Nexuses { nexuses: vec![] },
+            pool_stats: PoolIoStats { pool_stats: vec![] },
+            nexus_stats: NexusIoStats {
+                nexus_stats: vec![],
+            },
+            replica_stats: ReplicaIoStats {
+                replica_stats: vec![],
+            },
+            pool_counters: IoCounters::default(),
+            nexus_counters: IoCounters::default(),
+            replica_counters: IoCounters::default(),
+            volumes_qos: VolumesQos::default(),
+            pool_latency: IoLatencies::default(),
+            nexus_latency: IoLatencies::default(),
+            replica_latency: IoLatencies::default(),
+            exporter_stats: ExporterStats::default(),
+            grpc_exchanges: GrpcExchanges::default(),
+        }
+    }
+
+    /// Get pool mutably stored in struct.
+    pub(crate) fn pool_mut(&mut self) -> &mut Pools {
+        &mut self.pools
+    }
+
+    /// Get a reference to the Pool capacity history.
+    pub(crate) fn pool_capacity_history(&self) -> &PoolCapacityHistory {
+        &self.pool_capacity_history
+    }
+
+    /// Get mutable reference to the Pool capacity history.
+    pub(crate) fn pool_capacity_history_mut(&mut self) -> &mut PoolCapacityHistory {
+        &mut self.pool_capacity_history
+    }
+
+    /// Get a reference to BlockDevices.
+    pub(crate) fn block_device(&self) -> &BlockDevices {
+        &self.block_devices
+    }
+
+    /// Get mutable reference to BlockDevices.
+    pub(crate) fn block_device_mut(&mut self) -> &mut BlockDevices {
+        &mut self.block_devices
+    }
+
+    /// Get a reference to Nexuses.
+    pub(crate) fn nexus(&self) -> &Nexuses {
+        &self.nexuses
+    }
+
+    /// Get mutable reference to Nexuses.
+    pub(crate) fn nexus_mut(&mut self) -> &mut Nexuses {
+        &mut self.nexuses
+    }
+
+    /// Get mutable reference to PoolIOStats.
+    pub(crate) fn pool_iostat_mut(&mut self) -> &mut PoolIoStats {
+        &mut self.pool_stats
+    }
+
+    /// Get mutable reference to NexusIOStats.
+    pub(crate) fn nexus_iostat_mut(&mut self) -> &mut NexusIoStats {
+        &mut self.nexus_stats
+    }
+
+    /// Get a reference to NexusIoStats.
+    pub(crate) fn nexus_iostat(&self) -> &NexusIoStats {
+        &self.nexus_stats
+    }
+
+    /// Get a reference to Pool.
+    pub(crate) fn pool(&self) -> &Pools {
+        &self.pools
+    }
+
+    /// Get a reference to PoolIoStats.
+    pub(crate) fn pool_iostat(&self) -> &PoolIoStats {
+        &self.pool_stats
+    }
+
+    /// Get a reference to ReplicaIoStats.
+    pub(crate) fn replica_iostat(&self) -> &ReplicaIoStats {
+        &self.replica_stats
+    }
+
+    /// Get mutable reference to ReplicaIOStats.
+    pub(crate) fn replica_iostat_mut(&mut self) -> &mut ReplicaIoStats {
+        &mut self.replica_stats
+    }
+
+    /// Get a reference to the Pool io counters.
+    pub(crate) fn pool_counters(&self) -> &IoCounters {
+        &self.pool_counters
+    }
+
+    /// Get mutable reference to the Pool io counters.
+    pub(crate) fn pool_counters_mut(&mut self) -> &mut IoCounters {
+        &mut self.pool_counters
+    }
+
+    /// Get a reference to the Nexus io counters.
+    pub(crate) fn nexus_counters(&self) -> &IoCounters {
+        &self.nexus_counters
+    }
+
+    /// Get mutable reference to the Nexus io counters.
+    pub(crate) fn nexus_counters_mut(&mut self) -> &mut IoCounters {
+        &mut self.nexus_counters
+    }
+
+    /// Get a reference to the Replica io counters.
+    pub(crate) fn replica_counters(&self) -> &IoCounters {
+        &self.replica_counters
+    }
+
+    /// Get mutable reference to the Replica io counters.
+    pub(crate) fn replica_counters_mut(&mut self) -> &mut IoCounters {
+        &mut self.replica_counters
+    }
+
+    /// Get a reference to the io rates of the volumes.
+    pub(crate) fn volumes_qos(&self) -> &VolumesQos {
+        &self.volumes_qos
+    }
+
+    /// Get mutable reference to the io rates of the volumes.
+    pub(crate) fn volumes_qos_mut(&mut self) -> &mut VolumesQos {
+        &mut self.volumes_qos
+    }
+
+    /// Get a reference to the Pool latency histograms.
+    pub(crate) fn pool_latency(&self) -> &IoLatencies {
+        &self.pool_latency
+    }
+
+    /// Get mutable reference to the Pool latency histograms.
+    pub(crate) fn pool_latency_mut(&mut self) -> &mut IoLatencies {
+        &mut self.pool_latency
+    }
+
+    /// Get a reference to the Nexus latency histograms.
+    pub(crate) fn nexus_latency(&self) -> &IoLatencies {
+        &self.nexus_latency
+    }
+
+    /// Get mutable reference to the Nexus latency histograms.
+    pub(crate) fn nexus_latency_mut(&mut self) -> &mut IoLatencies {
+        &mut self.nexus_latency
+    }
+
+    /// Get a reference to the Replica latency histograms.
+    pub(crate) fn replica_latency(&self) -> &IoLatencies {
+        &self.replica_latency
+    }
+
+    /// Get mutable reference to the Replica latency histograms.
+    pub(crate) fn replica_latency_mut(&mut self) -> &mut IoLatencies {
+        &mut self.replica_latency
+    }
+
+    /// Get a reference to ExporterStats.
+    pub(crate) fn exporter_stats(&self) -> &ExporterStats {
+        &self.exporter_stats
+    }
+
+    /// Get mutable reference to ExporterStats.
+    pub(crate) fn exporter_stats_mut(&mut self) -> &mut ExporterStats {
+        &mut self.exporter_stats
+    }
+
+    /// Get a reference to the last gRPC exchanges with io-engine.
+    pub(crate) fn grpc_exchanges(&self) -> &GrpcExchanges {
+        &self.grpc_exchanges
+    }
+}
+
+impl Default for Data {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Populates the Resource cache of the node from its io-engine.
+pub(crate) async fn store_node_data(
+    client: &dyn IoEngineClient,
+    cache: &Mutex<Cache>,
+    node: &str,
+    config: &ExporterConfig,
+) {
+    let start = Instant::now();
+    let window = config.capacity_forecast_window();
+    let _ = pool::store_pool_info_data(client, cache, node, window).await;
+    let _ = block_device::store_block_device_data(client, cache, node).await;
+    let _ = nexus::store_nexus_info_data(client, cache, node).await;
+    let _ = pool_stat::store_pool_stats_data(client, cache, node).await;
+    let _ = nexus_stat::store_nexus_stats_data(client, cache, node).await;
+    let _ = replica_stat::store_replica_stats_data(client, cache, node).await;
+    exporter::store_collect_stats(cache, node, start.elapsed(), client.connection_stats());
+    exporter::store_grpc_exchanges(cache, node, client.grpc_exchanges());
+}
+
+/// Populates Resource cache struct periodically, sleeping for the polling time between updates.
+pub(crate) async fn store_resource_data(
+    client: &dyn IoEngineClient,
+    cache: &Mutex<Cache>,
+    node: &str,
+    config: &ExporterConfig,
+    polling_time: Duration,
+) {
+    loop {
+        // The retries of the requests of a poll must not delay the next one.
+        client.start_poll(polling_time);
+        store_node_data(client, cache, node, config).await;
+        sleep(polling_time).await;
+    }
+}
@@ -0,0 +1,54 @@
+use super::{Cache, ResourceOps, ResourceType};
+use crate::client::{
+    nexus::{NexusInfo, Nexuses},
+    IoEngineClient,
+};
+use std::sync::Mutex;
+use tracing::error;
+
+impl ResourceOps for Nexuses {
+    type ResourceVec = Vec<NexusInfo>;
+
+    fn set(&mut self, val: Self::ResourceVec) {
+        self.nexuses = val
+    }
+
+    fn invalidate(&mut self) {
+        self.nexuses = vec![]
+    }
+}
+
+/// To store nexus and rebuild data in cache.
+pub(crate) async fn store_nexus_info_data(
+    client: &dyn IoEngineClient,
+    cache: &Mutex<Cache>,
+    node: &str,
+) -> Result<(), ()> {
+    let nexuses = client.list_nexuses().await;
+    let mut cache = match cache.lock() {
+        Ok(cache) => cache,
+        Err(error) => {
+            error!(%error, "Error while getting cache resource");
+            return Err(());
+        }
+    };
+    let nexus_cache = cache.node_mut(node);
+    match nexuses {
+        Ok(nexuses) => {
+            nexus_cache.nexus_mut().set(nexuses.nexuses);
+            nexus_cache
+                .exporter_stats_mut()
+                .record_success(ResourceType::Nexus);
+        }
+        // invalidate cache in case of error
+        Err(error) => {
+            error!(?error, "Error getting nexus data, invalidating nexus cache");
+            nexus_cache.nexus_mut().invalidate();
+            nexus_cache
+                .exporter_stats_mut()
+                .record_failure(ResourceType::Nexus);
+            return Err(());
+        }
+    };
+    Ok(())
+}
@@ -1,9 +1,9 @@
-use super::{Cache, ResourceOps};
+use super::{Cache, ResourceOps, ResourceType};
 use crate::client::{
-    grpc_client::GrpcClient,
     nexus_stat::{NexusIoStat, NexusIoStats},
+    IoEngineClient,
 };
-use std::ops::DerefMut;
+use std::sync::Mutex;
 use tracing::error;
 
 impl ResourceOps for NexusIoStats {
@@ -18,19 +18,29 @@ impl ResourceOps for NexusIoStats {
     }
 }
 
-pub(crate) async fn store_nexus_stats_data(client: &GrpcClient) -> Result<(), ()> {
+pub(crate) async fn store_nexus_stats_data(
+    client: &dyn IoEngineClient,
+    cache: &Mutex<Cache>,
+    node: &str,
+) -> Result<(), ()> {
     let nexus_stats = client.get_nexus_iostat().await;
-    let mut cache = match Cache::get_cache().lock() {
+    let mut cache = match cache.lock() {
         Ok(cache) => cache,
         Err(error) => {
             error!(%error, "Error while getting cache resource");
             return Err(());
         }
     };
-    let nexus_cache = cache.deref_mut();
+    let nexus_cache = cache.node_mut(node);
     match nexus_stats {
         Ok(nexus) => {
+            nexus_cache.nexus_counters_mut().update(&nexus.nexus_stats);
+            nexus_cache.nexus_latency_mut().update(&nexus.nexus_stats);
+            nexus_cache.volumes_qos_mut().update(&nexus.nexus_stats);
             nexus_cache.nexus_iostat_mut().set(nexus.nexus_stats);
+            nexus_cache
+                .exporter_stats_mut()
+                .record_success(ResourceType::NexusIoStat);
         }
         // invalidate cache in case of error
         Err(error) => {
@@ -39,6 +49,9 @@ pub(crate) async fn store_nexus_stats_data(client: &GrpcClient) -> Result<(), ()
                 "Error getting nexus stats data, invalidating nexus stats cache"
             );
             nexus_cache.nexus_iostat_mut().invalidate();
+            nexus_cache
+                .exporter_stats_mut()
+                .record_failure(ResourceType::NexusIoStat);
             return Err(());
         }
     };
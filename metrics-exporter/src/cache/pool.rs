@@ -1,9 +1,9 @@
-use super::{Cache, ResourceOps};
+use super::{Cache, ResourceOps, ResourceType};
 use crate::client::{
-    grpc_client::GrpcClient,
     pool::{PoolInfo, Pools},
+    IoEngineClient,
 };
-use std::ops::DerefMut;
+use std::{sync::Mutex, time::Duration};
 use tracing::error;
 
 impl ResourceOps for Pools {
@@ -18,25 +18,40 @@ impl ResourceOps for Pools {
     }
 }
 
-/// To store pools state and capacity data in cache.
-pub(crate) async fn store_pool_info_data(client: &GrpcClient) -> Result<(), ()> {
+/// To store pools state and capacity data in cache, keeping the capacity samples of the forecast
+/// window.
+pub(crate) async fn store_pool_info_data(
+    client: &dyn IoEngineClient,
+    cache: &Mutex<Cache>,
+    node: &str,
+    window: Duration,
+) -> Result<(), ()> {
     let pools = client.list_pools().await;
-    let mut cache = match Cache::get_cache().lock() {
+    let mut cache = match cache.lock() {
         Ok(cache) => cache,
         Err(error) => {
             error!(%error, "Error while getting cache resource");
             return Err(());
         }
     };
-    let pools_cache = cache.deref_mut();
+    let pools_cache = cache.node_mut(node);
     match pools {
         Ok(pools) => {
+            pools_cache
+                .pool_capacity_history_mut()
+                .update(&pools.pools, window);
             pools_cache.pool_mut().set(pools.pools);
+            pools_cache
+                .exporter_stats_mut()
+                .record_success(ResourceType::Pool);
         }
         // invalidate cache in case of error
         Err(error) => {
             error!(?error, "Error getting pools data, invalidating pools cache");
             pools_cache.pool_mut().invalidate();
+            pools_cache
+                .exporter_stats_mut()
+                .record_failure(ResourceType::Pool);
             return Err(());
         }
     };
@@ -1,9 +1,9 @@
-use super::{Cache, ResourceOps};
+use super::{Cache, ResourceOps, ResourceType};
 use crate::client::{
-    grpc_client::GrpcClient,
     pool_stat::{PoolIoStat, PoolIoStats},
+    IoEngineClient,
 };
-use std::ops::DerefMut;
+use std::sync::Mutex;
 use tracing::error;
 
 impl ResourceOps for PoolIoStats {
@@ -19,24 +19,36 @@ impl ResourceOps for PoolIoStats {
 }
 
 /// To store pool iostat data in cache.
-pub(crate) async fn store_pool_stats_data(client: &GrpcClient) -> Result<(), ()> {
+pub(crate) async fn store_pool_stats_data(
+    client: &dyn IoEngineClient,
+    cache: &Mutex<Cache>,
+    node: &str,
+) -> Result<(), ()> {
     let pool_stats = client.get_pool_iostat().await;
-    let mut cache = match Cache::get_cache().lock() {
+    let mut cache = match cache.lock() {
         Ok(cache) => cache,
         Err(error) => {
             error!(%error, "Error while getting cache resource");
             return Err(());
         }
     };
-    let pools_cache = cache.deref_mut();
+    let pools_cache = cache.node_mut(node);
     match pool_stats {
         Ok(pools) => {
+            pools_cache.pool_counters_mut().update(&pools.pool_stats);
+            pools_cache.pool_latency_mut().update(&pools.pool_stats);
             pools_cache.pool_iostat_mut().set(pools.pool_stats);
+            pools_cache
+                .exporter_stats_mut()
+                .record_success(ResourceType::PoolIoStat);
         }
         // invalidate cache in case of error
         Err(error) => {
             error!(?error, "Error getting pools data, invalidating pools cache");
             pools_cache.pool_iostat_mut().invalidate();
+            pools_cache
+                .exporter_stats_mut()
+                .record_failure(ResourceType::PoolIoStat);
             return Err(());
         }
     };
@@ -0,0 +1,171 @@
+use super::counter::MonotonicCounter;
+use crate::client::nexus_stat::NexusIoStat;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Instant,
+};
+
+/// Io rates and mix of a volume over a polling interval.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct IoRates {
+    /// Read ops per second.
+    pub(crate) read_iops: f64,
+    /// Write ops per second.
+    pub(crate) write_iops: f64,
+    /// Bytes read per second.
+    pub(crate) read_throughput: f64,
+    /// Bytes written per second.
+    pub(crate) write_throughput: f64,
+    /// Average size of the read ops in bytes, if any.
+    pub(crate) avg_read_size: Option<f64>,
+    /// Average size of the write ops in bytes, if any.
+    pub(crate) avg_write_size: Option<f64>,
+    /// Share of the read ops in all the read and write ops, if any.
+    pub(crate) read_ratio: Option<f64>,
+}
+
+/// Counters of a volume from which its io rates are derived.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct VolumeQos {
+    num_read_ops: MonotonicCounter,
+    bytes_read: MonotonicCounter,
+    num_write_ops: MonotonicCounter,
+    bytes_written: MonotonicCounter,
+    num_unmap_ops: MonotonicCounter,
+    bytes_unmapped: MonotonicCounter,
+    /// Time of the last sample.
+    #[serde(skip)]
+    last_sample: Option<Instant>,
+    /// Io rates over the last polling interval.
+    rates: Option<IoRates>,
+}
+
+impl VolumeQos {
+    /// Update the counters with a new sample from io-engine taken at the given time, and derive
+    /// the io rates over the interval since the previous sample.
+    fn update(&mut self, stat: &NexusIoStat, now: Instant) {
+        self.num_read_ops.update(stat.num_read_ops());
+        self.bytes_read.update(stat.bytes_read());
+        self.num_write_ops.update(stat.num_write_ops());
+        self.bytes_written.update(stat.bytes_written());
+        self.num_unmap_ops.update(stat.num_unmap_ops());
+        self.bytes_unmapped.update(stat.bytes_unmapped());
+        if let Some(last_sample) = self.last_sample {
+            let seconds = now.saturating_duration_since(last_sample).as_secs_f64();
+            if seconds > 0.0 {
+                self.rates = Some(self.interval_rates(seconds));
+            }
+        }
+        self.last_sample = Some(now);
+    }
+
+    /// Derive the io rates from the increase of the counters over the interval in seconds.
+    fn interval_rates(&self, seconds: f64) -> IoRates {
+        let read_ops = self.num_read_ops.increase() as f64;
+        let write_ops = self.num_write_ops.increase() as f64;
+        let read_bytes = self.bytes_read.increase() as f64;
+        let write_bytes = self.bytes_written.increase() as f64;
+        let ratio = |part: f64, total: f64| (total > 0.0).then(|| part / total);
+        IoRates {
+            read_iops: read_ops / seconds,
+            write_iops: write_ops / seconds,
+            read_throughput: read_bytes / seconds,
+            write_throughput: write_bytes / seconds,
+            avg_read_size: ratio(read_bytes, read_ops),
+            avg_write_size: ratio(write_bytes, write_ops),
+            read_ratio: ratio(read_ops, read_ops + write_ops),
+        }
+    }
+
+    /// Get the io rates over the last polling interval, once two samples have been taken.
+    pub(crate) fn rates(&self) -> Option<IoRates> {
+        self.rates
+    }
+
+    /// Get total number of unmap ops.
+    pub(crate) fn num_unmap_ops(&self) -> u64 {
+        self.num_unmap_ops.value()
+    }
+
+    /// Get total bytes unmapped.
+    pub(crate) fn bytes_unmapped(&self) -> u64 {
+        self.bytes_unmapped.value()
+    }
+}
+
+/// Io rates of all the volumes, by nexus name.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct VolumesQos {
+    volumes: HashMap<String, VolumeQos>,
+}
+
+impl VolumesQos {
+    /// Update the volumes with the new samples, dropping volumes which no longer exist.
+    pub(crate) fn update(&mut self, stats: &[NexusIoStat]) {
+        let now = Instant::now();
+        let names = stats.iter().map(|stat| stat.name()).collect::<HashSet<_>>();
+        self.volumes.retain(|name, _| names.contains(name));
+        for stat in stats {
+            self.volumes
+                .entry(stat.name().clone())
+                .or_default()
+                .update(stat, now);
+        }
+    }
+
+    /// Get the io rates of all the volumes which have them.
+    pub(crate) fn rates(&self) -> impl Iterator<Item = IoRates> + '_ {
+        self.volumes.values().filter_map(VolumeQos::rates)
+    }
+
+    /// Get the io rates and unmap counters of the volume of the nexus.
+    pub(crate) fn get(&self, name: &str) -> Option<&VolumeQos> {
+        self.volumes.get(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_volume_qos() {
+        let sample = |reads, writes, unmaps| {
+            NexusIoStat::from(rpc::v1::stats::IoStats {
+                name: "nexus-1".to_string(),
+                num_read_ops: reads,
+                bytes_read: reads * 4096,
+                num_write_ops: writes,
+                bytes_written: writes * 65536,
+                num_unmap_ops: unmaps,
+                bytes_unmapped: unmaps * 1048576,
+                tick_rate: 1,
+                ..Default::default()
+            })
+        };
+        let start = Instant::now();
+        let mut qos = VolumeQos::default();
+        qos.update(&sample(100, 10, 1), start);
+        assert_eq!(qos.rates(), None);
+
+        qos.update(&sample(400, 110, 3), start + Duration::from_secs(10));
+        let rates = qos.rates().unwrap();
+        assert_eq!(rates.read_iops, 30.0);
+        assert_eq!(rates.write_iops, 10.0);
+        assert_eq!(rates.read_throughput, 30.0 * 4096.0);
+        assert_eq!(rates.avg_read_size, Some(4096.0));
+        assert_eq!(rates.avg_write_size, Some(65536.0));
+        assert_eq!(rates.read_ratio, Some(0.75));
+        assert_eq!(qos.num_unmap_ops(), 3);
+        assert_eq!(qos.bytes_unmapped(), 3 * 1048576);
+
+        // No io during the interval.
+        qos.update(&sample(400, 110, 3), start + Duration::from_secs(20));
+        let rates = qos.rates().unwrap();
+        assert_eq!(rates.read_iops, 0.0);
+        assert_eq!(rates.avg_read_size, None);
+        assert_eq!(rates.read_ratio, None);
+    }
+}
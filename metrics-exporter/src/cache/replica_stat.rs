@@ -1,9 +1,9 @@
-use super::{Cache, ResourceOps};
+use super::{Cache, ResourceOps, ResourceType};
 use crate::client::{
-    grpc_client::GrpcClient,
     replica_stat::{ReplicaIoStat, ReplicaIoStats},
+    IoEngineClient,
 };
-use std::ops::DerefMut;
+use std::sync::Mutex;
 use tracing::error;
 
 impl ResourceOps for ReplicaIoStats {
@@ -19,21 +19,35 @@ impl ResourceOps for ReplicaIoStats {
 }
 
 /// To store replica iostat data in cache.
-pub(crate) async fn store_replica_stats_data(client: &GrpcClient) -> Result<(), ()> {
+pub(crate) async fn store_replica_stats_data(
+    client: &dyn IoEngineClient,
+    cache: &Mutex<Cache>,
+    node: &str,
+) -> Result<(), ()> {
     let replica_stats = client.get_replica_iostat().await;
-    let mut cache = match Cache::get_cache().lock() {
+    let mut cache = match cache.lock() {
         Ok(cache) => cache,
         Err(error) => {
             error!(%error, "Error while getting cache resource");
             return Err(());
         }
     };
-    let replica_cache = cache.deref_mut();
+    let replica_cache = cache.node_mut(node);
     match replica_stats {
         Ok(replicas) => {
+            replica_cache
+                .replica_counters_mut()
+                .update(&replicas.replica_stats);
+            replica_cache
+                .replica_latency_mut()
+                .update(&replicas.replica_stats);
             replica_cache
                 .replica_iostat_mut()
                 .set(replicas.replica_stats);
+            replica_cache
+                .exporter_stats_mut()
+                .record_success(ResourceType::ReplicaIoStat);
+            cache.update_volume_replica_counters(node);
         }
         // invalidate cache in case of error
         Err(error) => {
@@ -42,6 +56,9 @@ pub(crate) async fn store_replica_stats_data(client: &GrpcClient) -> Result<(),
                 "Error getting replica stats data, invalidating replica cache"
             );
             replica_cache.replica_iostat_mut().invalidate();
+            replica_cache
+                .exporter_stats_mut()
+                .record_failure(ResourceType::ReplicaIoStat);
             return Err(());
         }
     };
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+/// Size of the sectors in which io-engine reports the size of the block devices.
+const SECTOR_SIZE: u64 = 512;
+
+/// This stores the facts of a block device of the node.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct BlockDeviceInfo {
+    devname: String,
+    devpath: String,
+    devlinks: Vec<String>,
+    model: String,
+    size: u64,
+    connection_type: String,
+    is_rotational: bool,
+}
+
+impl BlockDeviceInfo {
+    /// Get the device name, eg: `/dev/sdb`.
+    pub(crate) fn devname(&self) -> &String {
+        &self.devname
+    }
+
+    /// Get the model of the device.
+    pub(crate) fn model(&self) -> &String {
+        &self.model
+    }
+
+    /// Get the size of the device in bytes.
+    pub(crate) fn size_bytes(&self) -> u64 {
+        self.size.saturating_mul(SECTOR_SIZE)
+    }
+
+    /// Get the type of connection of the device, eg: `sata`, `nvme` or `virtio`.
+    pub(crate) fn connection_type(&self) -> &String {
+        &self.connection_type
+    }
+
+    /// Whether the device is rotational, ie: an HDD as opposed to an SSD.
+    pub(crate) fn is_rotational(&self) -> bool {
+        self.is_rotational
+    }
+
+    /// Whether the device is known under the path, ie: its name, sysfs path or one of its links.
+    fn has_path(&self, path: &str) -> bool {
+        let devname = self.devname.as_str();
+        devname == path
+            || path.strip_prefix("/dev/") == Some(devname)
+            || self.devpath == path
+            || self.devlinks.iter().any(|link| link == path)
+    }
+}
+
+/// Array of BlockDeviceInfo objects.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct BlockDevices {
+    pub(crate) devices: Vec<BlockDeviceInfo>,
+}
+
+impl BlockDevices {
+    /// Find the block device backing the disk of a pool. Disks not backed by a device of the
+    /// node, eg: `malloc:///malloc0`, have none.
+    pub(crate) fn find(&self, disk: &str) -> Option<&BlockDeviceInfo> {
+        let path = disk_device_path(disk)?;
+        self.devices.iter().find(|device| device.has_path(path))
+    }
+}
+
+/// Get the device path of the disk of a pool, which is either a path or a uri with the path and
+/// query parameters, eg: `aio:///dev/sdb?uuid=<uuid>`.
+fn disk_device_path(disk: &str) -> Option<&str> {
+    let path = match disk.split_once("://") {
+        Some((_, path)) => path,
+        None => disk,
+    };
+    let path = path.split('?').next().unwrap_or(path);
+    path.starts_with("/dev/").then_some(path)
+}
+
+impl From<rpc::v1::host::BlockDevice> for BlockDeviceInfo {
+    fn from(value: rpc::v1::host::BlockDevice) -> Self {
+        Self {
+            devname: value.devname,
+            devpath: value.devpath,
+            devlinks: value.devlinks,
+            model: value.model,
+            size: value.size,
+            connection_type: value.connection_type,
+            is_rotational: value.is_rotational,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(devname: &str, devlinks: &[&str]) -> BlockDeviceInfo {
+        BlockDeviceInfo {
+            devname: devname.to_string(),
+            devpath: format!("/devices/pci0000:00/block/{devname}"),
+            devlinks: devlinks.iter().map(|link| link.to_string()).collect(),
+            model: "disk".to_string(),
+            size: 2048,
+            connection_type: "sata".to_string(),
+            is_rotational: false,
+        }
+    }
+
+    #[test]
+    fn test_find_disk_device() {
+        let devices = BlockDevices {
+            devices: vec![
+                device("/dev/sda", &[]),
+                device("/dev/sdb", &["/dev/disk/by-id/ata-disk-b"]),
+            ],
+        };
+        let find = |disk| devices.find(disk).map(|device| device.devname().as_str());
+        assert_eq!(find("/dev/sda"), Some("/dev/sda"));
+        assert_eq!(find("aio:///dev/sdb?uuid=1a2b"), Some("/dev/sdb"));
+        assert_eq!(find("uring:///dev/disk/by-id/ata-disk-b"), Some("/dev/sdb"));
+        assert_eq!(find("aio:///dev/sdc"), None);
+        assert_eq!(find("malloc:///malloc0?size_mb=64"), None);
+        assert_eq!(devices.devices[0].size_bytes(), 1024 * 1024);
+    }
+}
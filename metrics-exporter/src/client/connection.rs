@@ -0,0 +1,256 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tonic::Code;
+use tracing::{info, warn};
+
+/// Maximum backoff between the retries of a request.
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(5);
+
+/// State of the gRPC connection to io-engine.
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum_macros::AsRefStr,
+    strum_macros::EnumIter,
+)]
+#[strum(serialize_all = "snake_case")]
+pub(crate) enum ConnectionState {
+    /// No request has reached io-engine yet.
+    #[default]
+    Connecting,
+    /// The last request reached io-engine.
+    Connected,
+    /// The last request could not reach io-engine.
+    Disconnected,
+    /// Too many consecutive requests could not reach io-engine, requests are not sent until the
+    /// cooldown elapses.
+    CircuitOpen,
+}
+
+/// Retry and circuit breaker policy of the requests to io-engine.
+#[derive(Debug, Clone)]
+pub(crate) struct RetryPolicy {
+    /// Number of retries of a request which could not reach io-engine.
+    retries: u32,
+    /// Backoff before the first retry, doubled on each retry.
+    backoff: Duration,
+    /// Number of consecutive requests which could not reach io-engine to open the circuit.
+    failure_threshold: u32,
+    /// Duration for which the circuit stays open before a request is let through again.
+    cooldown: Duration,
+}
+
+impl RetryPolicy {
+    /// Return a new `Self` with the retries, backoff and circuit breaker settings.
+    pub(crate) fn new(
+        retries: u32,
+        backoff: Duration,
+        failure_threshold: u32,
+        cooldown: Duration,
+    ) -> Self {
+        Self {
+            retries,
+            backoff,
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+        }
+    }
+
+    /// Backoff before the given retry of a request.
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(MAX_RETRY_BACKOFF)
+    }
+}
+
+/// Whether the request failed because io-engine could not be reached, as opposed to io-engine
+/// failing the request.
+pub(crate) fn is_connection_error(status: &tonic::Status) -> bool {
+    matches!(status.code(), Code::Unavailable | Code::DeadlineExceeded)
+}
+
+/// Statistics of the gRPC connection to io-engine.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub(crate) struct ConnectionStats {
+    state: ConnectionState,
+    consecutive_failures: u32,
+    retries: u64,
+    reconnects: u64,
+    circuit_opened: u64,
+}
+
+impl ConnectionStats {
+    /// Get the state of the connection.
+    pub(crate) fn state(&self) -> ConnectionState {
+        self.state
+    }
+
+    /// Get the number of consecutive requests which could not reach io-engine.
+    pub(crate) fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    /// Get the number of retried requests.
+    pub(crate) fn retries(&self) -> u64 {
+        self.retries
+    }
+
+    /// Get the number of times io-engine was reached again after being unreachable.
+    pub(crate) fn reconnects(&self) -> u64 {
+        self.reconnects
+    }
+
+    /// Get the number of times the circuit was opened.
+    pub(crate) fn circuit_opened(&self) -> u64 {
+        self.circuit_opened
+    }
+}
+
+/// Tracks the state of the gRPC connection to io-engine from the outcome of the requests, and
+/// opens the circuit when io-engine is unreachable.
+#[derive(Debug)]
+pub(crate) struct ConnectionTracker {
+    policy: RetryPolicy,
+    opened_at: Option<Instant>,
+    /// Time after which the requests of the current poll are not retried anymore.
+    retry_deadline: Option<Instant>,
+    stats: ConnectionStats,
+}
+
+impl ConnectionTracker {
+    /// Return a new `Self` with the retry policy.
+    pub(crate) fn new(policy: RetryPolicy) -> Self {
+        Self {
+            policy,
+            opened_at: None,
+            retry_deadline: None,
+            stats: ConnectionStats::default(),
+        }
+    }
+
+    /// Start a poll of io-engine, whose requests are only retried until the retry budget is
+    /// spent, so that the retries of all its requests do not delay the next poll.
+    pub(crate) fn start_poll(&mut self, retry_budget: Duration) {
+        self.retry_deadline = Some(Instant::now() + retry_budget);
+    }
+
+    /// Get the statistics of the connection.
+    pub(crate) fn stats(&self) -> &ConnectionStats {
+        &self.stats
+    }
+
+    /// Whether a request can be sent, ie: the circuit is closed or its cooldown has elapsed.
+    pub(crate) fn allow_request(&self) -> bool {
+        match self.opened_at {
+            Some(opened_at) => opened_at.elapsed() >= self.policy.cooldown,
+            None => true,
+        }
+    }
+
+    /// Record a request which reached io-engine, closing the circuit.
+    pub(crate) fn record_success(&mut self) {
+        if matches!(
+            self.stats.state,
+            ConnectionState::Disconnected | ConnectionState::CircuitOpen
+        ) {
+            info!("Reconnected to io-engine");
+            self.stats.reconnects += 1;
+        }
+        self.stats.state = ConnectionState::Connected;
+        self.stats.consecutive_failures = 0;
+        self.opened_at = None;
+    }
+
+    /// Record a request which could not reach io-engine, opening the circuit when the failure
+    /// threshold is reached, or when the request let through after the cooldown failed.
+    pub(crate) fn record_failure(&mut self) {
+        self.stats.consecutive_failures += 1;
+        if self.opened_at.is_some() {
+            self.opened_at = Some(Instant::now());
+        } else if self.stats.consecutive_failures >= self.policy.failure_threshold {
+            warn!(
+                failures = self.stats.consecutive_failures,
+                cooldown = ?self.policy.cooldown,
+                "io-engine is unreachable, opening the circuit"
+            );
+            self.opened_at = Some(Instant::now());
+            self.stats.state = ConnectionState::CircuitOpen;
+            self.stats.circuit_opened += 1;
+        } else {
+            self.stats.state = ConnectionState::Disconnected;
+        }
+    }
+
+    /// Record the retry of a request which could not reach io-engine and get the backoff before
+    /// it, unless the request was retried enough times or the retry budget of the poll is spent,
+    /// in which case the request is recorded as failed, once for all its attempts.
+    pub(crate) fn retry(&mut self, retry: u32) -> Option<Duration> {
+        let backoff = self.policy.backoff(retry);
+        if retry >= self.policy.retries
+            || self
+                .retry_deadline
+                .is_some_and(|deadline| Instant::now() + backoff >= deadline)
+        {
+            self.record_failure();
+            return None;
+        }
+        self.stats.retries += 1;
+        Some(backoff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_breaker() {
+        let policy = RetryPolicy::new(1, Duration::from_millis(100), 2, Duration::from_secs(60));
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff(10), MAX_RETRY_BACKOFF);
+
+        let mut tracker = ConnectionTracker::new(policy);
+        assert_eq!(tracker.stats().state(), ConnectionState::Connecting);
+        tracker.record_success();
+        assert_eq!(tracker.stats().state(), ConnectionState::Connected);
+        assert_eq!(tracker.stats().reconnects(), 0);
+
+        tracker.record_failure();
+        assert_eq!(tracker.stats().state(), ConnectionState::Disconnected);
+        assert!(tracker.allow_request());
+        tracker.record_failure();
+        assert_eq!(tracker.stats().state(), ConnectionState::CircuitOpen);
+        assert_eq!(tracker.stats().circuit_opened(), 1);
+        assert!(!tracker.allow_request());
+
+        tracker.record_success();
+        assert_eq!(tracker.stats().state(), ConnectionState::Connected);
+        assert_eq!(tracker.stats().reconnects(), 1);
+        assert_eq!(tracker.stats().consecutive_failures(), 0);
+        assert!(tracker.allow_request());
+
+        // Requests are retried only until the retries or the retry budget of the poll are spent,
+        // and a request is only recorded as failed once all its attempts failed.
+        assert_eq!(tracker.retry(0), Some(Duration::from_millis(100)));
+        assert_eq!(tracker.stats().consecutive_failures(), 0);
+        assert_eq!(tracker.retry(1), None);
+        assert_eq!(tracker.stats().consecutive_failures(), 1);
+        assert_eq!(tracker.stats().state(), ConnectionState::Disconnected);
+        tracker.record_success();
+        tracker.start_poll(Duration::from_millis(50));
+        assert_eq!(tracker.retry(0), None);
+        assert_eq!(tracker.stats().consecutive_failures(), 1);
+        tracker.start_poll(Duration::from_secs(1));
+        assert_eq!(tracker.retry(0), Some(Duration::from_millis(100)));
+        assert_eq!(tracker.stats().consecutive_failures(), 1);
+        assert_eq!(tracker.stats().retries(), 2);
+    }
+}
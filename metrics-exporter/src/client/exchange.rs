@@ -0,0 +1,49 @@
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Debug, time::SystemTime};
+
+/// A response or error of a gRPC method, along with the time it was received.
+#[derive(Serialize, Debug, Clone)]
+pub(crate) struct Recorded {
+    /// Time at which it was received, in RFC 3339 format.
+    time: String,
+    /// Debug representation of the raw response or of the error.
+    value: String,
+}
+
+impl Recorded {
+    fn now(value: String) -> Self {
+        Self {
+            time: humantime::format_rfc3339(SystemTime::now()).to_string(),
+            value,
+        }
+    }
+}
+
+/// The last response and the last error of a gRPC method.
+#[derive(Serialize, Debug, Default, Clone)]
+pub(crate) struct GrpcExchange {
+    last_response: Option<Recorded>,
+    last_error: Option<Recorded>,
+}
+
+/// The last exchanges with io-engine of each gRPC method, recorded to be served on the debug
+/// endpoints.
+#[derive(Serialize, Debug, Default, Clone)]
+pub(crate) struct GrpcExchanges {
+    #[serde(flatten)]
+    methods: BTreeMap<&'static str, GrpcExchange>,
+}
+
+impl GrpcExchanges {
+    /// Record the raw response of the method.
+    pub(crate) fn record_response(&mut self, method: &'static str, response: &impl Debug) {
+        self.methods.entry(method).or_default().last_response =
+            Some(Recorded::now(format!("{response:?}")));
+    }
+
+    /// Record the error of the method.
+    pub(crate) fn record_error(&mut self, method: &'static str, error: &impl Debug) {
+        self.methods.entry(method).or_default().last_error =
+            Some(Recorded::now(format!("{error:?}")));
+    }
+}
@@ -0,0 +1,176 @@
+use super::{
+    connection::RetryPolicy,
+    grpc_client::{GrpcClient, GrpcContext, Timeouts},
+};
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Server,
+};
+use rpc::v1::{host, nexus, pool, stats};
+use std::{
+    convert::Infallible,
+    future::{ready, Ready},
+    net::TcpListener,
+    sync::Arc,
+    time::Duration,
+};
+use tonic::{
+    body::BoxBody,
+    codec::ProstCodec,
+    codegen::http::{Request, Response, Uri},
+    server::{Grpc, UnaryService},
+    Status,
+};
+
+/// Responses served by the fake io-engine.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FakeIoEngine {
+    pub(crate) pools: Vec<pool::Pool>,
+    pub(crate) block_devices: Vec<host::BlockDevice>,
+    pub(crate) nexuses: Vec<nexus::Nexus>,
+    pub(crate) rebuild_stats: Vec<nexus::RebuildStatsResponse>,
+    pub(crate) pool_stats: Vec<stats::IoStats>,
+    pub(crate) nexus_stats: Vec<stats::IoStats>,
+    pub(crate) replica_stats: Vec<stats::ReplicaIoStats>,
+}
+
+/// Unary gRPC method of the fake io-engine, answering the request with the function, or with a
+/// not found error when the function has no response for it.
+struct Method<F>(F);
+
+impl<Req, Resp, F> UnaryService<Req> for Method<F>
+where
+    F: FnMut(Req) -> Option<Resp>,
+{
+    type Response = Resp;
+    type Future = Ready<Result<tonic::Response<Resp>, Status>>;
+
+    fn call(&mut self, request: tonic::Request<Req>) -> Self::Future {
+        let response = (self.0)(request.into_inner())
+            .map(tonic::Response::new)
+            .ok_or_else(|| Status::not_found("No fake response for the request"));
+        ready(response)
+    }
+}
+
+/// Answer the gRPC request with the function, decoding and encoding the messages with prost.
+async fn unary<Req, Resp, F>(request: Request<Body>, function: F) -> Response<BoxBody>
+where
+    Req: prost::Message + Default + Send + 'static,
+    Resp: prost::Message + Send + 'static,
+    F: FnMut(Req) -> Option<Resp> + Send + 'static,
+{
+    Grpc::new(ProstCodec::<Resp, Req>::default())
+        .unary(Method(function), request)
+        .await
+}
+
+impl FakeIoEngine {
+    /// Serve the gRPC methods called by the exporter in the background, and get a client of it.
+    /// Requests are routed by their method name, whichever the service.
+    pub(crate) fn serve(self) -> GrpcClient {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind the fake io-engine");
+        let address = listener.local_addr().expect("Failed to get the address");
+        let fake = Arc::new(self);
+        let make_service = make_service_fn(move |_| {
+            let fake = fake.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let fake = fake.clone();
+                    async move { Ok::<_, Infallible>(fake.handle(request).await) }
+                }))
+            }
+        });
+        let server = Server::from_tcp(listener)
+            .expect("Failed to serve the fake io-engine")
+            .http2_only(true)
+            .serve(make_service);
+        tokio::spawn(server);
+
+        let endpoint = Uri::builder()
+            .scheme("http")
+            .authority(address.to_string())
+            .path_and_query("")
+            .build()
+            .expect("Invalid fake io-engine uri");
+        let timeouts = Timeouts::new(Duration::from_secs(1), Duration::from_secs(5));
+        let policy = RetryPolicy::new(0, Duration::ZERO, 5, Duration::from_secs(30));
+        GrpcClient::new(GrpcContext::new(endpoint, timeouts), policy)
+    }
+
+    /// Answer the request to the method with the fake responses.
+    async fn handle(&self, request: Request<Body>) -> Response<BoxBody> {
+        let method = request
+            .uri()
+            .path()
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        match method.as_str() {
+            "ListPools" => {
+                let pools = self.pools.clone();
+                unary(request, move |_: pool::ListPoolOptions| {
+                    Some(pool::ListPoolsResponse {
+                        pools: pools.clone(),
+                    })
+                })
+                .await
+            }
+            "ListBlockDevices" => {
+                let list = self.block_devices.clone();
+                unary(request, move |_: host::ListBlockDevicesRequest| {
+                    Some(host::ListBlockDevicesResponse { list: list.clone() })
+                })
+                .await
+            }
+            "ListNexus" => {
+                let nexus_list = self.nexuses.clone();
+                unary(request, move |_: nexus::ListNexusOptions| {
+                    Some(nexus::ListNexusResponse {
+                        nexus_list: nexus_list.clone(),
+                    })
+                })
+                .await
+            }
+            "GetRebuildStats" => {
+                let rebuild_stats = self.rebuild_stats.clone();
+                unary(request, move |request: nexus::RebuildStatsRequest| {
+                    rebuild_stats
+                        .iter()
+                        .find(|stats| stats.uri == request.uri)
+                        .cloned()
+                })
+                .await
+            }
+            "GetPoolIoStats" => {
+                let io_stats = self.pool_stats.clone();
+                unary(request, move |_: stats::ListStatsOption| {
+                    Some(stats::PoolIoStatsResponse {
+                        stats: io_stats.clone(),
+                    })
+                })
+                .await
+            }
+            "GetNexusIoStats" => {
+                let io_stats = self.nexus_stats.clone();
+                unary(request, move |_: stats::ListStatsOption| {
+                    Some(stats::NexusIoStatsResponse {
+                        stats: io_stats.clone(),
+                    })
+                })
+                .await
+            }
+            "GetReplicaIoStats" => {
+                let io_stats = self.replica_stats.clone();
+                unary(request, move |_: stats::ListStatsOption| {
+                    Some(stats::ReplicaIoStatsResponse {
+                        stats: io_stats.clone(),
+                    })
+                })
+                .await
+            }
+            _ => Status::unimplemented(method).to_http(),
+        }
+    }
+}
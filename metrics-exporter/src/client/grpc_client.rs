@@ -0,0 +1,384 @@
+use crate::{config::ExporterConfig, error::ExporterError};
+
+use crate::client::{
+    block_device::{BlockDeviceInfo, BlockDevices},
+    connection::{is_connection_error, ConnectionStats, ConnectionTracker, RetryPolicy},
+    exchange::GrpcExchanges,
+    nexus::{NexusInfo, Nexuses, RebuildInfo},
+    nexus_stat::{NexusIoStat, NexusIoStats},
+    pool::{PoolInfo, Pools},
+    pool_stat::{PoolIoStat, PoolIoStats},
+    replica_stat::{ReplicaIoStat, ReplicaIoStats},
+    IoEngineClient,
+};
+use actix_web::http::Uri;
+use std::{
+    fmt::Debug,
+    future::Future,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::sleep;
+use tonic::transport::Channel;
+use tracing::{debug, error};
+
+/// Timeout for gRPC connection.
+#[derive(Debug, Clone)]
+pub struct Timeouts {
+    connect: Duration,
+    request: Duration,
+}
+
+impl Timeouts {
+    /// Return a new `Self` with the connect and request timeouts.
+    pub fn new(connect: Duration, request: Duration) -> Self {
+        Self { connect, request }
+    }
+    /// Timeout to establish connection to the node.
+    pub fn connect(&self) -> Duration {
+        self.connect
+    }
+    /// Timeout for the request itself.
+    pub fn request(&self) -> Duration {
+        self.request
+    }
+}
+
+/// Context for Grpc client.
+#[derive(Debug, Clone)]
+pub(crate) struct GrpcContext {
+    endpoint: tonic::transport::Endpoint,
+}
+
+impl GrpcContext {
+    pub fn new(endpoint: Uri, timeouts: Timeouts) -> Self {
+        let endpoint = tonic::transport::Endpoint::from(endpoint)
+            .connect_timeout(timeouts.connect())
+            .timeout(timeouts.request());
+        Self { endpoint }
+    }
+}
+
+/// The V1 PoolClient.
+type PoolClient = rpc::v1::pool::pool_rpc_client::PoolRpcClient<Channel>;
+type StatsClient = rpc::v1::stats::StatsRpcClient<Channel>;
+type NexusClient = rpc::v1::nexus::nexus_rpc_client::NexusRpcClient<Channel>;
+type HostClient = rpc::v1::host::host_rpc_client::HostRpcClient<Channel>;
+
+/// A wrapper for client for the V1 dataplane interface.
+#[derive(Clone, Debug)]
+pub(crate) struct MayaClientV1 {
+    pub(crate) pool: PoolClient,
+    pub(crate) stats: StatsClient,
+    pub(crate) nexus: NexusClient,
+    pub(crate) host: HostClient,
+}
+
+/// Dataplane grpc client.
+#[derive(Debug, Clone)]
+pub(crate) struct GrpcClient {
+    client: Option<MayaClientV1>,
+    connection: Arc<Mutex<ConnectionTracker>>,
+    /// The last exchanges of each method, only recorded for the debug endpoints.
+    exchanges: Option<Arc<Mutex<GrpcExchanges>>>,
+}
+
+impl GrpcClient {
+    /// Initialize v1 io engine gRPC client. The channel connects lazily and reconnects whenever
+    /// io-engine is reachable again, the state of the connection is tracked from the requests.
+    pub(crate) fn new(context: GrpcContext, policy: RetryPolicy) -> Self {
+        let channel = context.endpoint.connect_lazy();
+        let pool = PoolClient::new(channel.clone());
+        let stats = StatsClient::new(channel.clone());
+        let nexus = NexusClient::new(channel.clone());
+        let host = HostClient::new(channel);
+        Self {
+            client: Some(MayaClientV1 {
+                pool,
+                stats,
+                nexus,
+                host,
+            }),
+            connection: Arc::new(Mutex::new(ConnectionTracker::new(policy))),
+            exchanges: None,
+        }
+    }
+
+    /// Record the last response and error of each method, to be served on the debug endpoints.
+    pub(crate) fn with_exchanges(mut self) -> Self {
+        self.exchanges = Some(Arc::default());
+        self
+    }
+
+    /// Get the v1 api client.
+    pub(crate) fn client_v1(&self) -> Result<MayaClientV1, ExporterError> {
+        match self.client.clone() {
+            Some(client) => Ok(client),
+            None => Err(ExporterError::GrpcClientError(
+                "Could not get v1 client".to_string(),
+            )),
+        }
+    }
+
+    /// Run the function on the connection tracker, if its lock is not poisoned.
+    fn with_connection<R>(&self, function: impl FnOnce(&mut ConnectionTracker) -> R) -> Option<R> {
+        self.connection
+            .lock()
+            .ok()
+            .map(|mut connection| function(&mut connection))
+    }
+
+    /// Send the request to the method of io-engine, recording its outcome if the exchanges are
+    /// recorded.
+    async fn call<T, F, Fut>(&self, method: &'static str, request: F) -> Result<T, ExporterError>
+    where
+        T: Debug,
+        F: FnMut(MayaClientV1) -> Fut,
+        Fut: Future<Output = Result<tonic::Response<T>, tonic::Status>>,
+    {
+        let result = self.call_with_retries(request).await;
+        if let Some(Ok(mut exchanges)) = self.exchanges.as_ref().map(|exchanges| exchanges.lock()) {
+            match &result {
+                Ok(response) => exchanges.record_response(method, response),
+                Err(error) => exchanges.record_error(method, error),
+            }
+        }
+        result
+    }
+
+    /// Send the request to io-engine, retrying with backoff while io-engine cannot be reached,
+    /// and failing fast while the circuit is open.
+    async fn call_with_retries<T, F, Fut>(&self, mut request: F) -> Result<T, ExporterError>
+    where
+        F: FnMut(MayaClientV1) -> Fut,
+        Fut: Future<Output = Result<tonic::Response<T>, tonic::Status>>,
+    {
+        let client = self.client_v1()?;
+        let mut retry = 0;
+        loop {
+            if !self
+                .with_connection(|connection| connection.allow_request())
+                .unwrap_or(true)
+            {
+                return Err(ExporterError::GrpcClientError(
+                    "Circuit is open, io-engine is unreachable".to_string(),
+                ));
+            }
+            match request(client.clone()).await {
+                Ok(response) => {
+                    self.with_connection(ConnectionTracker::record_success);
+                    return Ok(response.into_inner());
+                }
+                Err(status) if is_connection_error(&status) => {
+                    let backoff = self.with_connection(|connection| connection.retry(retry));
+                    match backoff.flatten() {
+                        Some(backoff) => {
+                            debug!(%status, ?backoff, "Retrying grpc request");
+                            sleep(backoff).await;
+                            retry += 1;
+                        }
+                        None => return Err(ExporterError::GrpcResponseError(status.to_string())),
+                    }
+                }
+                // io-engine was reached, but failed the request.
+                Err(status) => {
+                    self.with_connection(ConnectionTracker::record_success);
+                    return Err(ExporterError::GrpcResponseError(status.to_string()));
+                }
+            }
+        }
+    }
+}
+
+/// Initialize mayastor grpc client of the io-engine listening on the pod ip.
+pub(crate) fn init_client(
+    pod_ip: IpAddr,
+    config: &ExporterConfig,
+) -> Result<GrpcClient, ExporterError> {
+    let timeout = Timeouts::new(Duration::from_secs(1), Duration::from_secs(5));
+    let endpoint = Uri::builder()
+        .scheme("https")
+        .authority(SocketAddr::new(pod_ip, 10124).to_string())
+        .path_and_query("")
+        .build()
+        .map_err(|error| ExporterError::InvalidURI(error.to_string()))?;
+    let ctx = GrpcContext::new(endpoint, timeout);
+    let policy = RetryPolicy::new(
+        config.grpc_retries(),
+        config.grpc_retry_backoff(),
+        config.grpc_circuit_failure_threshold(),
+        config.grpc_circuit_cooldown(),
+    );
+    let client = GrpcClient::new(ctx, policy);
+    if config.debug_endpoints() {
+        return Ok(client.with_exchanges());
+    }
+    Ok(client)
+}
+
+#[tonic::async_trait]
+impl IoEngineClient for GrpcClient {
+    /// Gets Capacity statistics of all pool on the io engine.
+    /// Maps the response to PoolInfo struct.
+    async fn list_pools(&self) -> Result<Pools, ExporterError> {
+        let pools = self
+            .call("ListPools", |mut client| async move {
+                client
+                    .pool
+                    .list_pools(rpc::v1::pool::ListPoolOptions::default())
+                    .await
+            })
+            .await?
+            .pools
+            .into_iter()
+            .map(PoolInfo::from)
+            .collect::<Vec<_>>();
+        Ok(Pools { pools })
+    }
+
+    /// Gets all block devices of the node, including the ones in use by the pools. Maps the
+    /// response to BlockDeviceInfo struct.
+    async fn list_block_devices(&self) -> Result<BlockDevices, ExporterError> {
+        let devices = self
+            .call("ListBlockDevices", |mut client| async move {
+                client
+                    .host
+                    .list_block_devices(rpc::v1::host::ListBlockDevicesRequest { all: true })
+                    .await
+            })
+            .await?
+            .list
+            .into_iter()
+            .map(BlockDeviceInfo::from)
+            .collect::<Vec<_>>();
+        Ok(BlockDevices { devices })
+    }
+
+    /// Gets Io Statistics of all pool on the io engine. Maps the response to PoolIoStat struct.
+    async fn get_pool_iostat(&self) -> Result<PoolIoStats, ExporterError> {
+        let pool_stats = self
+            .call("GetPoolIoStats", |mut client| async move {
+                client
+                    .stats
+                    .get_pool_io_stats(rpc::v1::stats::ListStatsOption { name: None })
+                    .await
+            })
+            .await?
+            .stats
+            .into_iter()
+            .map(PoolIoStat::from)
+            .collect::<Vec<_>>();
+        Ok(PoolIoStats { pool_stats })
+    }
+
+    /// Gets Io Statistics of all nexus on the io engine. Maps the response to NexusIoStat struct.
+    async fn get_nexus_iostat(&self) -> Result<NexusIoStats, ExporterError> {
+        let nexus_stats = self
+            .call("GetNexusIoStats", |mut client| async move {
+                client
+                    .stats
+                    .get_nexus_io_stats(rpc::v1::stats::ListStatsOption { name: None })
+                    .await
+            })
+            .await?
+            .stats
+            .into_iter()
+            .map(NexusIoStat::from)
+            .collect::<Vec<_>>();
+        Ok(NexusIoStats { nexus_stats })
+    }
+
+    /// Gets Io Statistics of all replica on the io engine. Maps the response to ReplicaIoStat
+    /// struct.
+    async fn get_replica_iostat(&self) -> Result<ReplicaIoStats, ExporterError> {
+        let replica_stats = self
+            .call("GetReplicaIoStats", |mut client| async move {
+                client
+                    .stats
+                    .get_replica_io_stats(rpc::v1::stats::ListStatsOption { name: None })
+                    .await
+            })
+            .await?
+            .stats
+            .into_iter()
+            .filter_map(|replica| ReplicaIoStat::try_from(replica).ok())
+            .collect::<Vec<_>>();
+        Ok(ReplicaIoStats { replica_stats })
+    }
+
+    /// Gets all nexus on the io engine along with the stats of the rebuilds in progress on them.
+    /// Maps the response to NexusInfo struct.
+    async fn list_nexuses(&self) -> Result<Nexuses, ExporterError> {
+        let nexus_list = self
+            .call("ListNexus", |mut client| async move {
+                client
+                    .nexus
+                    .list_nexus(rpc::v1::nexus::ListNexusOptions::default())
+                    .await
+            })
+            .await?
+            .nexus_list;
+
+        let mut nexuses = Vec::with_capacity(nexus_list.len());
+        for nexus in nexus_list {
+            let mut rebuild_stats = Vec::new();
+            // A negative rebuild progress means the child is not being rebuilt.
+            for child in nexus
+                .children
+                .iter()
+                .filter(|child| child.rebuild_progress >= 0)
+            {
+                let request = rpc::v1::nexus::RebuildStatsRequest {
+                    nexus_uuid: nexus.uuid.clone(),
+                    uri: child.uri.clone(),
+                };
+                let response = self
+                    .call("GetRebuildStats", |mut client| {
+                        let request = request.clone();
+                        async move { client.nexus.get_rebuild_stats(request).await }
+                    })
+                    .await;
+                match response {
+                    Ok(response) => rebuild_stats.push(RebuildInfo::from(response)),
+                    // The rebuild may have completed since the nexus was listed.
+                    Err(error) => {
+                        debug!(?error, nexus = %nexus.name, child = %child.uri, "Error getting rebuild stats")
+                    }
+                }
+            }
+            let mut nexus_info = NexusInfo::from(nexus);
+            nexus_info.set_rebuild_stats(rebuild_stats);
+            nexuses.push(nexus_info);
+        }
+        Ok(Nexuses { nexuses })
+    }
+
+    /// Get the last exchanges of each method, none if they are not recorded.
+    fn grpc_exchanges(&self) -> GrpcExchanges {
+        match self.exchanges.as_ref().map(|exchanges| exchanges.lock()) {
+            Some(Ok(exchanges)) => exchanges.clone(),
+            Some(Err(error)) => {
+                error!(%error, "Error while getting grpc exchanges");
+                GrpcExchanges::default()
+            }
+            None => GrpcExchanges::default(),
+        }
+    }
+
+    /// Start a poll of io-engine, retrying its requests for no longer than the retry budget.
+    fn start_poll(&self, retry_budget: Duration) {
+        self.with_connection(|connection| connection.start_poll(retry_budget));
+    }
+
+    /// Get the statistics of the connection to io-engine.
+    fn connection_stats(&self) -> ConnectionStats {
+        match self.connection.lock() {
+            Ok(connection) => connection.stats().clone(),
+            Err(error) => {
+                error!(%error, "Error while getting grpc connection state");
+                ConnectionStats::default()
+            }
+        }
+    }
+}
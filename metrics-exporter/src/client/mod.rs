@@ -0,0 +1,109 @@
+/// BlockDeviceInfo module.
+pub(crate) mod block_device;
+/// Grpc connection state module.
+pub(crate) mod connection;
+/// Recorded gRPC exchanges module.
+pub(crate) mod exchange;
+/// Fake io-engine gRPC server module.
+#[cfg(test)]
+pub(crate) mod fake;
+/// Grpc client module.
+pub(crate) mod grpc_client;
+/// NexusInfo module.
+pub(crate) mod nexus;
+/// NexusIoStats module.
+pub(crate) mod nexus_stat;
+/// PoolInfo module.
+pub(crate) mod pool;
+/// PoolIoStats module
+pub(crate) mod pool_stat;
+pub(crate) mod replica_stat;
+
+use crate::error::ExporterError;
+use block_device::BlockDevices;
+use connection::ConnectionStats;
+use exchange::GrpcExchanges;
+use nexus::Nexuses;
+use nexus_stat::{NexusIoStat, NexusIoStats};
+use pool::Pools;
+use pool_stat::{PoolIoStat, PoolIoStats};
+use replica_stat::{ReplicaIoStat, ReplicaIoStats};
+use std::time::Duration;
+
+/// Requests made to an io-engine to populate the cache. Implemented by the gRPC client, so that
+/// the cache can be populated from any implementation in tests.
+#[tonic::async_trait]
+pub(crate) trait IoEngineClient: Send + Sync {
+    /// Gets Capacity statistics of all pool on the io engine.
+    async fn list_pools(&self) -> Result<Pools, ExporterError>;
+    /// Gets all block devices of the node, including the ones in use by the pools.
+    async fn list_block_devices(&self) -> Result<BlockDevices, ExporterError>;
+    /// Gets Io Statistics of all pool on the io engine.
+    async fn get_pool_iostat(&self) -> Result<PoolIoStats, ExporterError>;
+    /// Gets Io Statistics of all nexus on the io engine.
+    async fn get_nexus_iostat(&self) -> Result<NexusIoStats, ExporterError>;
+    /// Gets Io Statistics of all replica on the io engine.
+    async fn get_replica_iostat(&self) -> Result<ReplicaIoStats, ExporterError>;
+    /// Gets all nexus on the io engine along with the stats of the rebuilds in progress on them.
+    async fn list_nexuses(&self) -> Result<Nexuses, ExporterError>;
+    /// Start a poll of io-engine, retrying its requests for no longer than the retry budget.
+    fn start_poll(&self, retry_budget: Duration);
+    /// Get the statistics of the connection to io-engine.
+    fn connection_stats(&self) -> ConnectionStats;
+    /// Get the last response and error of each gRPC method, if they are recorded.
+    fn grpc_exchanges(&self) -> GrpcExchanges;
+}
+
+/// Cumulative io counters common to all the IoStat objects.
+pub(crate) trait IoStat {
+    /// Name which identifies the resource.
+    fn name(&self) -> &String;
+    /// Total bytes read.
+    fn bytes_read(&self) -> u64;
+    /// Total number of read ops.
+    fn num_read_ops(&self) -> u64;
+    /// Total bytes written.
+    fn bytes_written(&self) -> u64;
+    /// Total number of write ops.
+    fn num_write_ops(&self) -> u64;
+    /// Total read latency in usec.
+    fn read_latency_us(&self) -> u64;
+    /// Total write latency in usec.
+    fn write_latency_us(&self) -> u64;
+}
+
+macro_rules! impl_io_stat {
+    ($stat:ty) => {
+        impl IoStat for $stat {
+            fn name(&self) -> &String {
+                self.name()
+            }
+            fn bytes_read(&self) -> u64 {
+                self.bytes_read()
+            }
+            fn num_read_ops(&self) -> u64 {
+                self.num_read_ops()
+            }
+            fn bytes_written(&self) -> u64 {
+                self.bytes_written()
+            }
+            fn num_write_ops(&self) -> u64 {
+                self.num_write_ops()
+            }
+            fn read_latency_us(&self) -> u64 {
+                self.read_latency_us()
+            }
+            fn write_latency_us(&self) -> u64 {
+                self.write_latency_us()
+            }
+        }
+    };
+}
+impl_io_stat!(PoolIoStat);
+impl_io_stat!(NexusIoStat);
+impl_io_stat!(ReplicaIoStat);
+
+/// Convert ticks to time in microseconds.
+fn ticks_to_time(tick: u64, tick_rate: u64) -> u64 {
+    ((tick as u128 * 1000000) / tick_rate as u128) as u64
+}
@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// This stores the progress of the rebuild of a nexus child.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct RebuildInfo {
+    child_uri: String,
+    blocks_total: u64,
+    blocks_transferred: u64,
+    blocks_remaining: u64,
+    block_size: u64,
+    progress: u64,
+    is_partial: bool,
+    start_time: Option<SystemTime>,
+}
+
+impl RebuildInfo {
+    /// Get the uri of the child being rebuilt.
+    pub(crate) fn child_uri(&self) -> &String {
+        &self.child_uri
+    }
+
+    /// Get the total number of blocks to be rebuilt.
+    pub(crate) fn blocks_total(&self) -> u64 {
+        self.blocks_total
+    }
+
+    /// Get the number of blocks transferred so far.
+    pub(crate) fn blocks_transferred(&self) -> u64 {
+        self.blocks_transferred
+    }
+
+    /// Get the number of blocks yet to be transferred.
+    pub(crate) fn blocks_remaining(&self) -> u64 {
+        self.blocks_remaining
+    }
+
+    /// Get the number of bytes transferred so far.
+    pub(crate) fn bytes_transferred(&self) -> u64 {
+        self.blocks_transferred * self.block_size
+    }
+
+    /// Get the rebuild progress in percent.
+    pub(crate) fn progress(&self) -> u64 {
+        self.progress
+    }
+
+    /// Whether only the blocks written while the child was offline are rebuilt.
+    pub(crate) fn is_partial(&self) -> bool {
+        self.is_partial
+    }
+
+    /// Get the time elapsed since the rebuild started, if known.
+    pub(crate) fn duration(&self) -> Option<Duration> {
+        self.start_time
+            .and_then(|start_time| SystemTime::now().duration_since(start_time).ok())
+    }
+}
+
+impl From<rpc::v1::nexus::RebuildStatsResponse> for RebuildInfo {
+    fn from(value: rpc::v1::nexus::RebuildStatsResponse) -> Self {
+        Self {
+            child_uri: value.uri,
+            blocks_total: value.blocks_total,
+            blocks_transferred: value.blocks_transferred,
+            blocks_remaining: value.blocks_remaining,
+            block_size: value.block_size,
+            progress: value.progress,
+            is_partial: value.is_partial,
+            start_time: value
+                .start_time
+                .map(|time| UNIX_EPOCH + Duration::new(time.seconds as u64, time.nanos as u32)),
+        }
+    }
+}
+
+/// This stores the state of a nexus child.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct ChildInfo {
+    uri: String,
+    state: u64,
+    reason: String,
+}
+
+impl ChildInfo {
+    /// Get the uri of the child.
+    pub(crate) fn uri(&self) -> &String {
+        &self.uri
+    }
+
+    /// Get state of the child.
+    pub(crate) fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Get the reason for the state of the child, eg: why it is faulted.
+    pub(crate) fn reason(&self) -> &String {
+        &self.reason
+    }
+}
+
+impl From<rpc::v1::nexus::Child> for ChildInfo {
+    fn from(value: rpc::v1::nexus::Child) -> Self {
+        let reason = match rpc::v1::nexus::ChildStateReason::try_from(value.reason) {
+            Ok(reason) => reason.as_str_name().to_lowercase(),
+            Err(_) => "unknown".to_string(),
+        };
+        Self {
+            uri: value.uri,
+            state: value.state as u64,
+            reason,
+        }
+    }
+}
+
+/// This stores the state, children and rebuild information of a nexus.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct NexusInfo {
+    name: String,
+    state: u64,
+    ana_state: u64,
+    children: Vec<ChildInfo>,
+    rebuilds: u32,
+    rebuild_stats: Vec<RebuildInfo>,
+}
+
+impl NexusInfo {
+    /// Get name of the nexus.
+    pub(crate) fn name(&self) -> &String {
+        &self.name
+    }
+
+    /// Get state of the nexus.
+    pub(crate) fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Get the NVMe ANA state of the nexus.
+    pub(crate) fn ana_state(&self) -> u64 {
+        self.ana_state
+    }
+
+    /// Get the children of the nexus.
+    pub(crate) fn children(&self) -> &Vec<ChildInfo> {
+        &self.children
+    }
+
+    /// Get the number of rebuilds in progress on the nexus.
+    pub(crate) fn rebuilds(&self) -> u32 {
+        self.rebuilds
+    }
+
+    /// Get the stats of the rebuilds in progress on the nexus.
+    pub(crate) fn rebuild_stats(&self) -> &Vec<RebuildInfo> {
+        &self.rebuild_stats
+    }
+
+    /// Set the stats of the rebuilds in progress on the nexus.
+    pub(crate) fn set_rebuild_stats(&mut self, rebuild_stats: Vec<RebuildInfo>) {
+        self.rebuild_stats = rebuild_stats;
+    }
+}
+
+impl From<rpc::v1::nexus::Nexus> for NexusInfo {
+    fn from(value: rpc::v1::nexus::Nexus) -> Self {
+        Self {
+            name: value.name,
+            state: value.state as u64,
+            ana_state: value.ana_state as u64,
+            children: value.children.into_iter().map(ChildInfo::from).collect(),
+            rebuilds: value.rebuilds,
+            rebuild_stats: vec![],
+        }
+    }
+}
+
+/// Array of NexusInfo objects.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct Nexuses {
+    pub(crate) nexuses: Vec<NexusInfo>,
+}
@@ -9,6 +9,8 @@ pub(crate) struct NexusIoStat {
     num_read_ops: u64,
     bytes_written: u64,
     num_write_ops: u64,
+    num_unmap_ops: u64,
+    bytes_unmapped: u64,
     read_latency_us: u64,
     write_latency_us: u64,
 }
@@ -39,6 +41,16 @@ impl NexusIoStat {
         self.num_write_ops
     }
 
+    /// Get total number of unmap ops performed by the nexus.
+    pub(crate) fn num_unmap_ops(&self) -> u64 {
+        self.num_unmap_ops
+    }
+
+    /// Get the total bytes unmapped by the nexus.
+    pub(crate) fn bytes_unmapped(&self) -> u64 {
+        self.bytes_unmapped
+    }
+
     /// Get total read latency in usec on the nexus.
     pub(crate) fn read_latency_us(&self) -> u64 {
         self.read_latency_us
@@ -64,6 +76,8 @@ impl From<rpc::v1::stats::IoStats> for NexusIoStat {
             num_read_ops: value.num_read_ops,
             bytes_written: value.bytes_written,
             num_write_ops: value.num_write_ops,
+            num_unmap_ops: value.num_unmap_ops,
+            bytes_unmapped: value.bytes_unmapped,
             read_latency_us: ticks_to_time(value.read_latency_ticks, value.tick_rate),
             write_latency_us: ticks_to_time(value.write_latency_ticks, value.tick_rate),
         }
@@ -8,6 +8,7 @@ pub(crate) struct PoolInfo {
     capacity: u64,
     state: u64,
     committed: u64,
+    disks: Vec<String>,
 }
 
 impl PoolInfo {
@@ -35,6 +36,11 @@ impl PoolInfo {
     pub(crate) fn state(&self) -> u64 {
         self.state
     }
+
+    /// Get the disks of the pool.
+    pub(crate) fn disks(&self) -> &Vec<String> {
+        &self.disks
+    }
 }
 
 /// Array of PoolInfo objects.
@@ -51,6 +57,7 @@ impl From<rpc::v1::pool::Pool> for PoolInfo {
             capacity: value.capacity,
             state: value.state as u64,
             committed: value.committed,
+            disks: value.disks,
         }
     }
 }
@@ -0,0 +1,181 @@
+use crate::{
+    cache::{store_node_data, Cache, SharedCache},
+    client::{
+        grpc_client::{init_client, GrpcClient},
+        IoEngineClient,
+    },
+    config::ExporterConfig,
+    error::ExporterError,
+};
+use k8s_openapi::api::core::v1::Pod;
+use kube::{api::ListParams, Api, Client};
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{task::JoinSet, time::sleep};
+use tracing::{error, warn};
+
+/// An io-engine pod of the cluster, scheduled on a node.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IoEnginePod {
+    node: String,
+    ip: IpAddr,
+}
+
+impl IoEnginePod {
+    /// Get the io-engine pod of the pod, if it is running on a node with an ip.
+    fn from_pod(pod: &Pod) -> Option<Self> {
+        let status = pod.status.as_ref()?;
+        if status.phase.as_deref() != Some("Running") {
+            return None;
+        }
+        let node = pod.spec.as_ref()?.node_name.clone()?;
+        let ip = status.pod_ip.as_ref()?.parse().ok()?;
+        Some(Self { node, ip })
+    }
+}
+
+/// Discovers the io-engine pods of the cluster through the Kubernetes API.
+pub(crate) struct Discovery {
+    api: Api<Pod>,
+    selector: String,
+}
+
+impl Discovery {
+    /// Create a discovery of the pods matching the label selector, in the namespace or in the
+    /// namespace of the exporter if none is given.
+    pub(crate) async fn new(
+        namespace: Option<String>,
+        selector: String,
+    ) -> Result<Self, ExporterError> {
+        let client = Client::try_default().await.map_err(|error| {
+            ExporterError::DiscoveryError(format!("Failed to create Kubernetes client: {error}"))
+        })?;
+        let api = match namespace {
+            Some(namespace) => Api::namespaced(client, &namespace),
+            None => Api::default_namespaced(client),
+        };
+        Ok(Self { api, selector })
+    }
+
+    /// List the running io-engine pods.
+    async fn list(&self) -> Result<Vec<IoEnginePod>, ExporterError> {
+        let pods = self
+            .api
+            .list(&ListParams::default().labels(&self.selector))
+            .await
+            .map_err(|error| {
+                ExporterError::DiscoveryError(format!("Failed to list io-engine pods: {error}"))
+            })?;
+        Ok(pods.iter().filter_map(IoEnginePod::from_pod).collect())
+    }
+}
+
+/// Populates the Resource cache of all the io-engine nodes of the cluster periodically, the nodes
+/// being refreshed concurrently. The nodes whose io-engine pod is gone are dropped from the cache.
+pub(crate) async fn store_cluster_data(
+    discovery: Discovery,
+    cache: SharedCache,
+    config: Arc<ExporterConfig>,
+    polling_time: Duration,
+) {
+    let mut clients = HashMap::<String, (IpAddr, GrpcClient)>::new();
+    loop {
+        match discovery.list().await {
+            Ok(pods) => update_clients(&mut clients, &cache, &config, pods),
+            // Keep refreshing the nodes known so far.
+            Err(error) => error!(?error, "Error while discovering io-engine pods"),
+        }
+
+        let mut refreshes = JoinSet::new();
+        for (node, (_, client)) in &clients {
+            let node = node.clone();
+            let client = client.clone();
+            let cache = cache.clone();
+            let config = config.clone();
+            // The retries of the requests of a poll must not delay the next one.
+            client.start_poll(polling_time);
+            refreshes.spawn(async move { store_node_data(&client, &cache, &node, &config).await });
+        }
+        while let Some(result) = refreshes.join_next().await {
+            if let Err(error) = result {
+                error!(%error, "Error while refreshing io-engine node");
+            }
+        }
+        sleep(polling_time).await;
+    }
+}
+
+/// Keep a client for each io-engine pod, replacing it when the pod ip has changed, and drop the
+/// nodes which no longer have an io-engine pod.
+fn update_clients(
+    clients: &mut HashMap<String, (IpAddr, GrpcClient)>,
+    cache: &Mutex<Cache>,
+    config: &ExporterConfig,
+    pods: Vec<IoEnginePod>,
+) {
+    clients.retain(|node, _| pods.iter().any(|pod| &pod.node == node));
+    for pod in pods {
+        if matches!(clients.get(&pod.node), Some((ip, _)) if *ip == pod.ip) {
+            continue;
+        }
+        match init_client(pod.ip, config) {
+            Ok(client) => {
+                clients.insert(pod.node, (pod.ip, client));
+            }
+            Err(error) => warn!(?error, node = %pod.node, "Error while creating io-engine client"),
+        }
+    }
+    let nodes = clients.keys().cloned().collect::<Vec<_>>();
+    match cache.lock() {
+        Ok(mut cache) => cache.retain_nodes(&nodes),
+        Err(error) => error!(%error, "Error while getting cache resource"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::{PodSpec, PodStatus};
+
+    fn pod(node: Option<&str>, ip: Option<&str>, phase: &str) -> Pod {
+        Pod {
+            spec: Some(PodSpec {
+                node_name: node.map(str::to_string),
+                ..Default::default()
+            }),
+            status: Some(PodStatus {
+                phase: Some(phase.to_string()),
+                pod_ip: ip.map(str::to_string),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_io_engine_pod() {
+        assert_eq!(
+            IoEnginePod::from_pod(&pod(Some("worker-0"), Some("10.1.0.4"), "Running")),
+            Some(IoEnginePod {
+                node: "worker-0".to_string(),
+                ip: "10.1.0.4".parse().unwrap(),
+            })
+        );
+        assert_eq!(
+            IoEnginePod::from_pod(&pod(Some("worker-0"), None, "Pending")),
+            None
+        );
+        assert_eq!(
+            IoEnginePod::from_pod(&pod(None, Some("10.1.0.4"), "Running")),
+            None
+        );
+        assert_eq!(
+            IoEnginePod::from_pod(&pod(Some("worker-0"), Some("invalid"), "Running")),
+            None
+        );
+    }
+}
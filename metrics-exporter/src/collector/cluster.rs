@@ -0,0 +1,195 @@
+use crate::{
+    cache::SharedCache, client::connection::ConnectionState, collector::init_cluster_gauge,
+};
+use prometheus::{
+    core::{Collector, Desc},
+    Gauge,
+};
+use std::fmt::Debug;
+use tracing::error;
+
+/// Collects the totals of all the io-engine nodes of the cluster from cache.
+#[derive(Clone, Debug)]
+pub(crate) struct ClusterTotalsCollector {
+    nodes: Gauge,
+    nodes_connected: Gauge,
+    diskpools: Gauge,
+    diskpool_total_size: Gauge,
+    diskpool_used_size: Gauge,
+    diskpool_committed_size: Gauge,
+    diskpool_commitment_ratio: Gauge,
+    diskpool_free_after_commit: Gauge,
+    volumes: Gauge,
+    volume_read_iops: Gauge,
+    volume_write_iops: Gauge,
+    volume_read_throughput: Gauge,
+    volume_write_throughput: Gauge,
+    cache: SharedCache,
+    descs: Vec<Desc>,
+}
+
+impl ClusterTotalsCollector {
+    /// Initialize all the metrics to be defined for cluster totals collector.
+    pub fn new(cache: SharedCache) -> Self {
+        let mut descs = Vec::new();
+        let nodes = init_cluster_gauge(
+            "io_engine_nodes",
+            "Number of io-engine nodes discovered in the cluster",
+            &mut descs,
+        );
+        let nodes_connected = init_cluster_gauge(
+            "io_engine_nodes_connected",
+            "Number of io-engine nodes reached by the last request",
+            &mut descs,
+        );
+        let diskpools =
+            init_cluster_gauge("diskpools", "Number of pools of all the nodes", &mut descs);
+        let diskpool_total_size = init_cluster_gauge(
+            "diskpool_total_size_bytes",
+            "Total size of the pools of all the nodes in bytes",
+            &mut descs,
+        );
+        let diskpool_used_size = init_cluster_gauge(
+            "diskpool_used_size_bytes",
+            "Used size of the pools of all the nodes in bytes",
+            &mut descs,
+        );
+        let diskpool_committed_size = init_cluster_gauge(
+            "diskpool_committed_size_bytes",
+            "Committed size of the pools of all the nodes in bytes",
+            &mut descs,
+        );
+        let diskpool_commitment_ratio = init_cluster_gauge(
+            "diskpool_commitment_ratio",
+            "Ratio of the committed size to the total size of the pools of all the nodes",
+            &mut descs,
+        );
+        let diskpool_free_after_commit = init_cluster_gauge(
+            "diskpool_free_after_commit_bytes",
+            "Total size minus committed size of the pools of all the nodes in bytes, negative when overcommitted",
+            &mut descs,
+        );
+        let volumes = init_cluster_gauge(
+            "volumes",
+            "Number of volume targets of all the nodes",
+            &mut descs,
+        );
+        let volume_read_iops = init_cluster_gauge(
+            "volume_read_iops",
+            "Read ops per second of all the volumes over the last polling interval",
+            &mut descs,
+        );
+        let volume_write_iops = init_cluster_gauge(
+            "volume_write_iops",
+            "Write ops per second of all the volumes over the last polling interval",
+            &mut descs,
+        );
+        let volume_read_throughput = init_cluster_gauge(
+            "volume_read_throughput_bytes_per_second",
+            "Bytes read per second from all the volumes over the last polling interval",
+            &mut descs,
+        );
+        let volume_write_throughput = init_cluster_gauge(
+            "volume_write_throughput_bytes_per_second",
+            "Bytes written per second to all the volumes over the last polling interval",
+            &mut descs,
+        );
+
+        Self {
+            nodes,
+            nodes_connected,
+            diskpools,
+            diskpool_total_size,
+            diskpool_used_size,
+            diskpool_committed_size,
+            diskpool_commitment_ratio,
+            diskpool_free_after_commit,
+            volumes,
+            volume_read_iops,
+            volume_write_iops,
+            volume_read_throughput,
+            volume_write_throughput,
+            cache,
+            descs,
+        }
+    }
+}
+
+impl Collector for ClusterTotalsCollector {
+    fn desc(&self) -> Vec<&prometheus::core::Desc> {
+        self.descs.iter().collect()
+    }
+
+    fn collect(&self) -> Vec<prometheus::proto::MetricFamily> {
+        let cache = match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(error) => {
+                error!(%error,"Error while getting cache resource");
+                return Vec::new();
+            }
+        };
+        let mut nodes = 0;
+        let mut nodes_connected = 0;
+        let mut diskpools = 0;
+        let (mut total_size, mut used_size, mut committed_size) = (0.0, 0.0, 0.0);
+        let mut volumes = 0;
+        let (mut read_iops, mut write_iops) = (0.0, 0.0);
+        let (mut read_throughput, mut write_throughput) = (0.0, 0.0);
+        for (_, cache_deref) in cache.nodes() {
+            nodes += 1;
+            if cache_deref.exporter_stats().connection().state() == ConnectionState::Connected {
+                nodes_connected += 1;
+            }
+            for pool in &cache_deref.pool().pools {
+                diskpools += 1;
+                total_size += pool.capacity() as f64;
+                used_size += pool.used() as f64;
+                committed_size += pool.committed() as f64;
+            }
+            volumes += cache_deref.nexus().nexuses.len();
+            for rates in cache_deref.volumes_qos().rates() {
+                read_iops += rates.read_iops;
+                write_iops += rates.write_iops;
+                read_throughput += rates.read_throughput;
+                write_throughput += rates.write_throughput;
+            }
+        }
+
+        self.nodes.set(nodes as f64);
+        self.nodes_connected.set(nodes_connected as f64);
+        self.diskpools.set(diskpools as f64);
+        self.diskpool_total_size.set(total_size);
+        self.diskpool_used_size.set(used_size);
+        self.diskpool_committed_size.set(committed_size);
+        if total_size > 0.0 {
+            self.diskpool_commitment_ratio
+                .set(committed_size / total_size);
+        }
+        self.diskpool_free_after_commit
+            .set(total_size - committed_size);
+        self.volumes.set(volumes as f64);
+        self.volume_read_iops.set(read_iops);
+        self.volume_write_iops.set(write_iops);
+        self.volume_read_throughput.set(read_throughput);
+        self.volume_write_throughput.set(write_throughput);
+        [
+            &self.nodes,
+            &self.nodes_connected,
+            &self.diskpools,
+            &self.diskpool_total_size,
+            &self.diskpool_used_size,
+            &self.diskpool_committed_size,
+            &self.diskpool_free_after_commit,
+            &self.volumes,
+            &self.volume_read_iops,
+            &self.volume_write_iops,
+            &self.volume_read_throughput,
+            &self.volume_write_throughput,
+        ]
+        .into_iter()
+        // Without any pool size, there is no commitment ratio.
+        .chain((total_size > 0.0).then_some(&self.diskpool_commitment_ratio))
+        .flat_map(|gauge| gauge.collect())
+        .collect()
+    }
+}
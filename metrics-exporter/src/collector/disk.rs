@@ -0,0 +1,161 @@
+use super::init_diskpool_disk_gauge_vec;
+use crate::cache::SharedCache;
+use prometheus::{
+    core::{Collector, Desc},
+    GaugeVec,
+};
+use std::fmt::Debug;
+use tracing::error;
+
+/// Collects the facts of the block devices backing the disks of the pools.
+#[derive(Clone, Debug)]
+pub(crate) struct PoolDiskCollector {
+    pool_disk_present: GaugeVec,
+    pool_disk_info: GaugeVec,
+    pool_disk_size: GaugeVec,
+    pool_disk_rotational: GaugeVec,
+    cache: SharedCache,
+    descs: Vec<Desc>,
+}
+
+impl PoolDiskCollector {
+    /// Initialize all the metrics to be defined for pool disk collector.
+    pub fn new(cache: SharedCache) -> Self {
+        let mut descs = Vec::new();
+        let pool_disk_present = init_diskpool_disk_gauge_vec(
+            "present",
+            "Whether the block device backing the disk of the pool is present on the node",
+            &[],
+            &mut descs,
+        );
+        let pool_disk_info = init_diskpool_disk_gauge_vec(
+            "info",
+            "Block device backing the disk of the pool, with its model, connection type and media",
+            &["device", "model", "connection_type", "media"],
+            &mut descs,
+        );
+        let pool_disk_size = init_diskpool_disk_gauge_vec(
+            "size_bytes",
+            "Size of the block device backing the disk of the pool in bytes",
+            &[],
+            &mut descs,
+        );
+        let pool_disk_rotational = init_diskpool_disk_gauge_vec(
+            "rotational",
+            "Whether the block device backing the disk of the pool is rotational",
+            &[],
+            &mut descs,
+        );
+
+        Self {
+            pool_disk_present,
+            pool_disk_info,
+            pool_disk_size,
+            pool_disk_rotational,
+            cache,
+            descs,
+        }
+    }
+}
+
+impl Collector for PoolDiskCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        self.descs.iter().collect()
+    }
+
+    fn collect(&self) -> Vec<prometheus::proto::MetricFamily> {
+        let cache = match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(error) => {
+                error!(%error,"Error while getting cache resource");
+                return Vec::new();
+            }
+        };
+        let mut metric_family = Vec::new();
+        for (node_name, cache_deref) in cache.nodes() {
+            let block_devices = cache_deref.block_device();
+            // The block devices could not be listed, the disks would wrongly appear to be missing.
+            if block_devices.devices.is_empty() {
+                continue;
+            }
+            for pool in &cache_deref.pool().pools {
+                for disk in pool.disks() {
+                    let labels = [node_name.as_str(), pool.name().as_str(), disk.as_str()];
+                    let device = block_devices.find(disk);
+                    let pool_disk_present = match self
+                        .pool_disk_present
+                        .get_metric_with_label_values(&labels)
+                    {
+                        Ok(pool_disk_present) => pool_disk_present,
+                        Err(error) => {
+                            error!(%error, "Error while creating pool_disk_present gauge with label values");
+                            return metric_family;
+                        }
+                    };
+                    pool_disk_present.set(device.is_some() as u8 as f64);
+                    let mut metric_vec = pool_disk_present.collect();
+                    metric_family.extend(metric_vec.pop());
+
+                    let Some(device) = device else {
+                        continue;
+                    };
+                    let media = match device.is_rotational() {
+                        true => "hdd",
+                        false => "ssd",
+                    };
+                    let info_labels = [
+                        labels[0],
+                        labels[1],
+                        labels[2],
+                        device.devname().as_str(),
+                        device.model().as_str(),
+                        device.connection_type().as_str(),
+                        media,
+                    ];
+                    let pool_disk_info = match self
+                        .pool_disk_info
+                        .get_metric_with_label_values(&info_labels)
+                    {
+                        Ok(pool_disk_info) => pool_disk_info,
+                        Err(error) => {
+                            error!(%error, "Error while creating pool_disk_info gauge with label values");
+                            return metric_family;
+                        }
+                    };
+                    pool_disk_info.set(1.0);
+                    let mut metric_vec = pool_disk_info.collect();
+                    metric_family.extend(metric_vec.pop());
+
+                    let pool_disk_size = match self
+                        .pool_disk_size
+                        .get_metric_with_label_values(&labels)
+                    {
+                        Ok(pool_disk_size) => pool_disk_size,
+                        Err(error) => {
+                            error!(%error, "Error while creating pool_disk_size gauge with label values");
+                            return metric_family;
+                        }
+                    };
+                    pool_disk_size.set(device.size_bytes() as f64);
+                    let mut metric_vec = pool_disk_size.collect();
+                    metric_family.extend(metric_vec.pop());
+
+                    let pool_disk_rotational = match self
+                        .pool_disk_rotational
+                        .get_metric_with_label_values(&labels)
+                    {
+                        Ok(pool_disk_rotational) => pool_disk_rotational,
+                        Err(error) => {
+                            error!(%error, "Error while creating pool_disk_rotational gauge with label values");
+                            return metric_family;
+                        }
+                    };
+                    pool_disk_rotational.set(device.is_rotational() as u8 as f64);
+                    let mut metric_vec = pool_disk_rotational.collect();
+                    metric_family.extend(metric_vec.pop());
+                }
+            }
+        }
+        metric_family
+    }
+}
@@ -0,0 +1,113 @@
+use crate::cache::MonotonicCounter;
+use prometheus::proto::{Bucket, Metric, MetricFamily};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Field number of the exemplar of a counter in `io.prometheus.client.Counter`.
+const COUNTER_EXEMPLAR_FIELD: u32 = 2;
+/// Field number of the exemplar of a histogram bucket in `io.prometheus.client.Bucket`.
+const BUCKET_EXEMPLAR_FIELD: u32 = 3;
+
+/// Exemplar of a counter or histogram bucket, as defined in `io.prometheus.client.Exemplar`.
+/// The generated protobuf messages of the prometheus crate have no exemplar field, so the
+/// exemplars are stored as unknown fields, which the protobuf encoder writes as is.
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct Exemplar {
+    #[prost(message, repeated, tag = "1")]
+    pub(crate) label: Vec<ExemplarLabel>,
+    #[prost(double, tag = "2")]
+    pub(crate) value: f64,
+    #[prost(message, optional, tag = "3")]
+    pub(crate) timestamp: Option<Timestamp>,
+}
+
+/// Label of an exemplar.
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct ExemplarLabel {
+    #[prost(string, tag = "1")]
+    pub(crate) name: String,
+    #[prost(string, tag = "2")]
+    pub(crate) value: String,
+}
+
+/// Time of an exemplar, as defined in `google.protobuf.Timestamp`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct Timestamp {
+    #[prost(int64, tag = "1")]
+    pub(crate) seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub(crate) nanos: i32,
+}
+
+impl Exemplar {
+    /// Create an exemplar of the value observed at the given time, with the labels.
+    pub(crate) fn new(labels: &[(&str, String)], value: f64, time: SystemTime) -> Self {
+        let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        Self {
+            label: labels
+                .iter()
+                .map(|(name, value)| ExemplarLabel {
+                    name: name.to_string(),
+                    value: value.clone(),
+                })
+                .collect(),
+            value,
+            timestamp: Some(Timestamp {
+                seconds: since_epoch.as_secs() as i64,
+                nanos: since_epoch.subsec_nanos() as i32,
+            }),
+        }
+    }
+
+    /// Get the time of the exemplar in seconds since the epoch, if any.
+    pub(crate) fn timestamp_secs(&self) -> Option<f64> {
+        self.timestamp
+            .as_ref()
+            .map(|timestamp| timestamp.seconds as f64 + timestamp.nanos as f64 / 1e9)
+    }
+}
+
+/// Set the exemplar of the counter metric, which must not have one yet.
+pub(crate) fn set_counter_exemplar(metric: &mut Metric, exemplar: &Exemplar) {
+    let exemplar = prost::Message::encode_to_vec(exemplar);
+    metric
+        .mut_counter()
+        .unknown_fields
+        .add_length_delimited(COUNTER_EXEMPLAR_FIELD, exemplar);
+}
+
+/// Set the exemplar of the histogram bucket, which must not have one yet.
+pub(crate) fn set_bucket_exemplar(bucket: &mut Bucket, exemplar: &Exemplar) {
+    let exemplar = prost::Message::encode_to_vec(exemplar);
+    bucket
+        .unknown_fields
+        .add_length_delimited(BUCKET_EXEMPLAR_FIELD, exemplar);
+}
+
+/// Set the last non-zero increase of the io counter as the exemplar of the metrics of the family.
+pub(crate) fn with_counter_exemplar(
+    mut metric_family: MetricFamily,
+    counter: &MonotonicCounter,
+) -> MetricFamily {
+    if let Some((increase, time)) = counter.exemplar() {
+        let exemplar = Exemplar::new(&[], increase as f64, time);
+        for metric in metric_family.mut_metric().iter_mut() {
+            set_counter_exemplar(metric, &exemplar);
+        }
+    }
+    metric_family
+}
+
+/// Get the exemplar of the counter metric, if any.
+pub(crate) fn counter_exemplar(metric: &Metric) -> Option<Exemplar> {
+    let values = metric
+        .get_counter()
+        .unknown_fields
+        .get(COUNTER_EXEMPLAR_FIELD)?;
+    prost::Message::decode(values.length_delimited.last()?.as_slice()).ok()
+}
+
+/// Get the exemplar of the histogram bucket, if any.
+pub(crate) fn bucket_exemplar(bucket: &Bucket) -> Option<Exemplar> {
+    let values = bucket.unknown_fields.get(BUCKET_EXEMPLAR_FIELD)?;
+    prost::Message::decode(values.length_delimited.last()?.as_slice()).ok()
+}
@@ -0,0 +1,231 @@
+use super::{init_exporter_counter_vec, init_exporter_gauge_vec};
+use crate::{cache::SharedCache, client::connection::ConnectionState};
+use prometheus::{
+    core::{Collector, Desc},
+    CounterVec, GaugeVec,
+};
+use std::{fmt::Debug, time::SystemTime};
+use strum::IntoEnumIterator;
+use tracing::error;
+
+/// Collects health metrics of the exporter itself from cache.
+#[derive(Clone, Debug)]
+pub(crate) struct ExporterHealthCollector {
+    grpc_requests: CounterVec,
+    last_success_timestamp: GaugeVec,
+    cache_age: GaugeVec,
+    collect_duration: GaugeVec,
+    grpc_connection_state: GaugeVec,
+    grpc_consecutive_failures: GaugeVec,
+    grpc_retries: CounterVec,
+    grpc_reconnects: CounterVec,
+    grpc_circuit_opened: CounterVec,
+    cache: SharedCache,
+    descs: Vec<Desc>,
+}
+
+impl ExporterHealthCollector {
+    /// Initialize all the metrics to be defined for exporter health collector.
+    pub fn new(cache: SharedCache) -> Self {
+        let mut descs = Vec::new();
+        let grpc_requests = init_exporter_counter_vec(
+            "grpc_requests_total",
+            "Total number of gRPC requests made to io-engine",
+            &["node", "resource", "result"],
+            &mut descs,
+        );
+        let last_success_timestamp = init_exporter_gauge_vec(
+            "last_success_timestamp_seconds",
+            "Unix time of the last successful fetch of the resource from io-engine",
+            &["node", "resource"],
+            &mut descs,
+        );
+        let cache_age = init_exporter_gauge_vec(
+            "cache_age_seconds",
+            "Age of the cached resource data in seconds",
+            &["node", "resource"],
+            &mut descs,
+        );
+        let collect_duration = init_exporter_gauge_vec(
+            "collect_duration_seconds",
+            "Duration of the last collection of all resources from io-engine in seconds",
+            &["node"],
+            &mut descs,
+        );
+        let grpc_connection_state = init_exporter_gauge_vec(
+            "grpc_connection_state",
+            "State of the gRPC connection to io-engine, 1 for the current state and 0 for the others",
+            &["node", "state"],
+            &mut descs,
+        );
+        let grpc_consecutive_failures = init_exporter_gauge_vec(
+            "grpc_consecutive_failures",
+            "Number of consecutive gRPC requests which could not reach io-engine",
+            &["node"],
+            &mut descs,
+        );
+        let grpc_retries = init_exporter_counter_vec(
+            "grpc_retries_total",
+            "Total number of retries of gRPC requests which could not reach io-engine",
+            &["node"],
+            &mut descs,
+        );
+        let grpc_reconnects = init_exporter_counter_vec(
+            "grpc_reconnects_total",
+            "Total number of times io-engine was reached again after being unreachable",
+            &["node"],
+            &mut descs,
+        );
+        let grpc_circuit_opened = init_exporter_counter_vec(
+            "grpc_circuit_opened_total",
+            "Total number of times gRPC requests were stopped because io-engine was unreachable",
+            &["node"],
+            &mut descs,
+        );
+
+        Self {
+            grpc_requests,
+            last_success_timestamp,
+            cache_age,
+            collect_duration,
+            grpc_connection_state,
+            grpc_consecutive_failures,
+            grpc_retries,
+            grpc_reconnects,
+            grpc_circuit_opened,
+            cache,
+            descs,
+        }
+    }
+}
+
+impl Collector for ExporterHealthCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        self.descs.iter().collect()
+    }
+
+    fn collect(&self) -> Vec<prometheus::proto::MetricFamily> {
+        let cache = match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(error) => {
+                error!(%error,"Error while getting cache resource");
+                return Vec::new();
+            }
+        };
+        let mut metric_family = Vec::new();
+        for (node_name, cache_deref) in cache.nodes() {
+            let exporter_stats = cache_deref.exporter_stats();
+            for (resource, requests) in exporter_stats.grpc_requests() {
+                for (result, count) in [
+                    ("success", requests.success()),
+                    ("failure", requests.failure()),
+                ] {
+                    match self.grpc_requests.get_metric_with_label_values(&[
+                        node_name.as_str(),
+                        resource.as_ref(),
+                        result,
+                    ]) {
+                        Ok(grpc_requests) => grpc_requests.inc_by(count as f64),
+                        Err(error) => {
+                            error!(%error, "Error while creating grpc_requests counter with label values");
+                            return metric_family;
+                        }
+                    }
+                }
+            }
+            metric_family.extend(self.grpc_requests.collect());
+
+            let now = SystemTime::now();
+            for (resource, last_success) in exporter_stats.last_success() {
+                let labels = [node_name.as_str(), resource.as_ref()];
+                match self
+                    .last_success_timestamp
+                    .get_metric_with_label_values(&labels)
+                {
+                    Ok(last_success_timestamp) => last_success_timestamp.set(
+                        last_success
+                            .duration_since(SystemTime::UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_secs_f64(),
+                    ),
+                    Err(error) => {
+                        error!(%error, "Error while creating last_success_timestamp gauge with label values");
+                        return metric_family;
+                    }
+                }
+                match self.cache_age.get_metric_with_label_values(&labels) {
+                    Ok(cache_age) => cache_age.set(
+                        now.duration_since(*last_success)
+                            .unwrap_or_default()
+                            .as_secs_f64(),
+                    ),
+                    Err(error) => {
+                        error!(%error, "Error while creating cache_age gauge with label values");
+                        return metric_family;
+                    }
+                }
+            }
+            metric_family.extend(self.last_success_timestamp.collect());
+            metric_family.extend(self.cache_age.collect());
+
+            if let Some(duration) = exporter_stats.collect_duration() {
+                match self
+                    .collect_duration
+                    .get_metric_with_label_values(&[node_name.as_str()])
+                {
+                    Ok(collect_duration) => collect_duration.set(duration.as_secs_f64()),
+                    Err(error) => {
+                        error!(%error, "Error while creating collect_duration gauge with label values");
+                        return metric_family;
+                    }
+                }
+                metric_family.extend(self.collect_duration.collect());
+            }
+
+            let connection = exporter_stats.connection();
+            for state in ConnectionState::iter() {
+                match self
+                    .grpc_connection_state
+                    .get_metric_with_label_values(&[node_name.as_str(), state.as_ref()])
+                {
+                    Ok(grpc_connection_state) => {
+                        grpc_connection_state.set((state == connection.state()) as u8 as f64)
+                    }
+                    Err(error) => {
+                        error!(%error, "Error while creating grpc_connection_state gauge with label values");
+                        return metric_family;
+                    }
+                }
+            }
+            metric_family.extend(self.grpc_connection_state.collect());
+            match self
+                .grpc_consecutive_failures
+                .get_metric_with_label_values(&[node_name.as_str()])
+            {
+                Ok(grpc_consecutive_failures) => {
+                    grpc_consecutive_failures.set(connection.consecutive_failures() as f64)
+                }
+                Err(error) => {
+                    error!(%error, "Error while creating grpc_consecutive_failures gauge with label values");
+                    return metric_family;
+                }
+            }
+            metric_family.extend(self.grpc_consecutive_failures.collect());
+            for (counter_vec, value) in [
+                (&self.grpc_retries, connection.retries()),
+                (&self.grpc_reconnects, connection.reconnects()),
+                (&self.grpc_circuit_opened, connection.circuit_opened()),
+            ] {
+                match counter_vec.get_metric_with_label_values(&[node_name.as_str()]) {
+                    Ok(counter) => counter.inc_by(value as f64),
+                    Err(error) => {
+                        error!(%error, "Error while creating grpc connection counter with label values");
+                        return metric_family;
+                    }
+                }
+                metric_family.extend(counter_vec.collect());
+            }
+        }
+        metric_family
+    }
+}
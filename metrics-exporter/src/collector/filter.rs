@@ -0,0 +1,141 @@
+use prometheus::proto::{LabelPair, Metric, MetricFamily, MetricType};
+use std::collections::HashMap;
+
+/// Drop the labels from all the metrics of the family, aggregating the series which only differ
+/// by the dropped labels: counters and histograms are summed, and gauges are averaged. The family
+/// must hold all the metrics of its name, as gathered by the registry.
+pub(crate) fn drop_labels(metric_family: &mut MetricFamily, labels: &[String]) {
+    let metric_type = metric_family.get_field_type();
+    let mut aggregated = Vec::<(Metric, u64)>::new();
+    let mut index = HashMap::<Vec<(String, String)>, usize>::new();
+
+    for mut metric in metric_family.take_metric().into_iter() {
+        let kept_labels = metric
+            .take_label()
+            .into_iter()
+            .filter(|label| !labels.contains(&label.get_name().to_string()))
+            .collect::<Vec<LabelPair>>();
+        let key = kept_labels
+            .iter()
+            .map(|label| (label.get_name().to_string(), label.get_value().to_string()))
+            .collect::<Vec<_>>();
+        metric.set_label(kept_labels.into());
+
+        match index.get(&key) {
+            Some(position) => {
+                let (existing, merged) = &mut aggregated[*position];
+                merge_metric(existing, &metric, metric_type);
+                *merged += 1;
+            }
+            None => {
+                index.insert(key, aggregated.len());
+                aggregated.push((metric, 1));
+            }
+        }
+    }
+
+    for (mut metric, merged) in aggregated {
+        if metric_type == MetricType::GAUGE && merged > 1 {
+            let average = metric.get_gauge().get_value() / merged as f64;
+            metric.mut_gauge().set_value(average);
+        }
+        metric_family.mut_metric().push(metric);
+    }
+}
+
+/// Add the value of the metric to the existing metric of the same type.
+fn merge_metric(existing: &mut Metric, metric: &Metric, metric_type: MetricType) {
+    match metric_type {
+        MetricType::COUNTER => {
+            let value = existing.get_counter().get_value() + metric.get_counter().get_value();
+            existing.mut_counter().set_value(value);
+        }
+        MetricType::GAUGE => {
+            let value = existing.get_gauge().get_value() + metric.get_gauge().get_value();
+            existing.mut_gauge().set_value(value);
+        }
+        MetricType::UNTYPED => {
+            let value = existing.get_untyped().get_value() + metric.get_untyped().get_value();
+            existing.mut_untyped().set_value(value);
+        }
+        MetricType::HISTOGRAM => {
+            let histogram = metric.get_histogram();
+            let existing = existing.mut_histogram();
+            existing.set_sample_count(existing.get_sample_count() + histogram.get_sample_count());
+            existing.set_sample_sum(existing.get_sample_sum() + histogram.get_sample_sum());
+            for (bucket, other) in existing.mut_bucket().iter_mut().zip(histogram.get_bucket()) {
+                bucket.set_cumulative_count(
+                    bucket.get_cumulative_count() + other.get_cumulative_count(),
+                );
+            }
+        }
+        MetricType::SUMMARY => {
+            let summary = metric.get_summary();
+            let existing = existing.mut_summary();
+            existing.set_sample_count(existing.get_sample_count() + summary.get_sample_count());
+            existing.set_sample_sum(existing.get_sample_sum() + summary.get_sample_sum());
+            // Quantiles cannot be aggregated.
+            existing.clear_quantile();
+        }
+    }
+}
+
+/// Number of series exposed for the metric, eg: a histogram exposes a series for each bucket
+/// along with the +Inf bucket, the sum and the count.
+fn series_count(metric: &Metric, metric_type: MetricType) -> usize {
+    match metric_type {
+        MetricType::HISTOGRAM => metric.get_histogram().get_bucket().len() + 3,
+        MetricType::SUMMARY => metric.get_summary().get_quantile().len() + 2,
+        _ => 1,
+    }
+}
+
+/// Limit the number of series across the metric families to the maximum, dropping the metrics
+/// beyond it. Returns the total number of series and the number of series dropped.
+pub(crate) fn limit_series(
+    metric_families: &mut Vec<MetricFamily>,
+    max_series: usize,
+) -> (usize, usize) {
+    let mut total = 0;
+    let mut dropped = 0;
+    for metric_family in metric_families.iter_mut() {
+        let metric_type = metric_family.get_field_type();
+        let metrics = metric_family.take_metric();
+        for metric in metrics.into_iter() {
+            let count = series_count(&metric, metric_type);
+            total += count;
+            if total - dropped > max_series {
+                dropped += count;
+            } else {
+                metric_family.mut_metric().push(metric);
+            }
+        }
+    }
+    metric_families.retain(|metric_family| !metric_family.get_metric().is_empty());
+    (total, dropped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{core::Collector, CounterVec, GaugeVec, Opts};
+
+    #[test]
+    fn test_limit_series() {
+        let gauge_vec = GaugeVec::new(Opts::new("used", "used"), &["name"]).unwrap();
+        let counter_vec = CounterVec::new(Opts::new("ops", "ops"), &["name"]).unwrap();
+        for name in ["a", "b", "c"] {
+            gauge_vec.with_label_values(&[name]).set(1.0);
+            counter_vec.with_label_values(&[name]).inc();
+        }
+        let mut metric_families = gauge_vec.collect();
+        metric_families.extend(counter_vec.collect());
+
+        assert_eq!(limit_series(&mut metric_families, 4), (6, 2));
+        assert_eq!(metric_families.len(), 2);
+        assert_eq!(metric_families[1].get_metric().len(), 1);
+
+        assert_eq!(limit_series(&mut metric_families, 2), (4, 2));
+        assert_eq!(metric_families.len(), 1);
+    }
+}
@@ -0,0 +1,69 @@
+use super::exemplar::{set_bucket_exemplar, Exemplar};
+use crate::cache::{LatencyHistogram, LATENCY_BUCKETS};
+use prometheus::{
+    core::{Desc, Describer},
+    proto::{Bucket, Histogram, LabelPair, Metric, MetricFamily, MetricType},
+    Opts,
+};
+
+/// Histogram metric exposing the latency histograms stored in cache.
+#[derive(Clone, Debug)]
+pub(crate) struct LatencyHistogramVec {
+    desc: Desc,
+}
+
+impl LatencyHistogramVec {
+    /// Create a new histogram metric with the provided options.
+    pub(crate) fn new(opts: Opts) -> prometheus::Result<Self> {
+        Ok(Self {
+            desc: opts.describe()?,
+        })
+    }
+
+    /// Get the descriptor of the metric.
+    pub(crate) fn desc(&self) -> &Desc {
+        &self.desc
+    }
+
+    /// Build the metric family with one histogram for each of the provided label values.
+    pub(crate) fn metric_family(
+        &self,
+        histograms: Vec<(Vec<String>, &LatencyHistogram)>,
+    ) -> MetricFamily {
+        let mut metric_family = MetricFamily::default();
+        metric_family.set_name(self.desc.fq_name.clone());
+        metric_family.set_help(self.desc.help.clone());
+        metric_family.set_field_type(MetricType::HISTOGRAM);
+        for (label_values, latency) in histograms {
+            let mut histogram = Histogram::default();
+            histogram.set_sample_count(latency.count());
+            histogram.set_sample_sum(latency.sum());
+            for ((upper_bound, count), exemplar) in LATENCY_BUCKETS
+                .iter()
+                .zip(latency.cumulative_buckets())
+                .zip(latency.exemplars())
+            {
+                let mut bucket = Bucket::default();
+                bucket.set_upper_bound(*upper_bound);
+                bucket.set_cumulative_count(count);
+                // The last interval observed in the bucket, along with its number of ops.
+                if let Some(exemplar) = exemplar {
+                    let labels = [("ops", exemplar.ops.to_string())];
+                    let exemplar = Exemplar::new(&labels, exemplar.latency, exemplar.time);
+                    set_bucket_exemplar(&mut bucket, &exemplar);
+                }
+                histogram.mut_bucket().push(bucket);
+            }
+            let mut metric = Metric::default();
+            for (name, value) in self.desc.variable_labels.iter().zip(label_values) {
+                let mut label = LabelPair::default();
+                label.set_name(name.clone());
+                label.set_value(value);
+                metric.mut_label().push(label);
+            }
+            metric.set_histogram(histogram);
+            metric_family.mut_metric().push(metric);
+        }
+        metric_family
+    }
+}
@@ -0,0 +1,273 @@
+use latency::LatencyHistogramVec;
+use prometheus::{
+    core::{Collector, Desc},
+    CounterVec, Gauge, GaugeVec, Opts,
+};
+
+pub(crate) mod cluster;
+pub(crate) mod disk;
+pub(crate) mod exemplar;
+pub(crate) mod exporter;
+pub(crate) mod filter;
+pub(crate) mod latency;
+pub(crate) mod nexus;
+pub(crate) mod nexus_stat;
+pub(crate) mod pool;
+pub(crate) mod pool_stat;
+pub(crate) mod rebuild;
+pub(crate) mod registry;
+pub(crate) mod replica_stat;
+pub(crate) mod volume;
+
+/// Initializes a GaugeVec metric for diskpool with the provided metric name, description and
+/// descriptors.
+fn init_diskpool_gauge_vec(
+    metric_name: &str,
+    metric_desc: &str,
+    descs: &mut Vec<Desc>,
+) -> GaugeVec {
+    let opts = Opts::new(metric_name, metric_desc)
+        .subsystem("diskpool")
+        .variable_labels(vec!["node".to_string(), "name".to_string()]);
+    let gauge_vec = GaugeVec::new(opts, &["node", "name"])
+        .unwrap_or_else(|_| panic!("Unable to create gauge metric type for {}", metric_name));
+    descs.extend(gauge_vec.desc().into_iter().cloned());
+    gauge_vec
+}
+
+/// Initializes a GaugeVec metric for a disk of a diskpool with the provided metric name,
+/// description, additional labels and descriptors.
+fn init_diskpool_disk_gauge_vec(
+    metric_name: &str,
+    metric_desc: &str,
+    disk_labels: &[&str],
+    descs: &mut Vec<Desc>,
+) -> GaugeVec {
+    let labels = ["node", "name", "disk"]
+        .into_iter()
+        .chain(disk_labels.iter().copied())
+        .collect::<Vec<_>>();
+    let opts = Opts::new(metric_name, metric_desc)
+        .subsystem("diskpool_disk")
+        .variable_labels(labels.iter().map(|label| label.to_string()).collect());
+    let gauge_vec = GaugeVec::new(opts, &labels)
+        .unwrap_or_else(|_| panic!("Unable to create gauge metric type for {}", metric_name));
+    descs.extend(gauge_vec.desc().into_iter().cloned());
+    gauge_vec
+}
+
+/// Initializes a Gauge metric totalling all the io-engine nodes of the cluster with the provided
+/// metric name, description and descriptors.
+fn init_cluster_gauge(metric_name: &str, metric_desc: &str, descs: &mut Vec<Desc>) -> Gauge {
+    let opts = Opts::new(metric_name, metric_desc).subsystem("cluster");
+    let gauge = Gauge::with_opts(opts)
+        .unwrap_or_else(|_| panic!("Unable to create gauge metric type for {}", metric_name));
+    descs.extend(gauge.desc().into_iter().cloned());
+    gauge
+}
+
+/// Initializes a GaugeVec metric for volume with the provided metric name, description and
+/// descriptors.
+fn init_volume_gauge_vec(metric_name: &str, metric_desc: &str, descs: &mut Vec<Desc>) -> GaugeVec {
+    let opts = Opts::new(metric_name, metric_desc)
+        .subsystem("volume")
+        .variable_labels(vec!["node".to_string(), "pv_name".to_string()]);
+    let gauge_vec = GaugeVec::new(opts, &["node", "pv_name"])
+        .unwrap_or_else(|_| panic!("Unable to create gauge metric type for {}", metric_name));
+    descs.extend(gauge_vec.desc().into_iter().cloned());
+    gauge_vec
+}
+
+/// Initializes a GaugeVec metric for a nexus child of a volume with the provided metric name,
+/// description, additional labels and descriptors.
+fn init_volume_child_gauge_vec(
+    metric_name: &str,
+    metric_desc: &str,
+    child_labels: &[&str],
+    descs: &mut Vec<Desc>,
+) -> GaugeVec {
+    let labels = ["node", "pv_name", "child"]
+        .into_iter()
+        .chain(child_labels.iter().copied())
+        .collect::<Vec<_>>();
+    let opts = Opts::new(metric_name, metric_desc)
+        .subsystem("volume")
+        .variable_labels(labels.iter().map(|label| label.to_string()).collect());
+    let gauge_vec = GaugeVec::new(opts, &labels)
+        .unwrap_or_else(|_| panic!("Unable to create gauge metric type for {}", metric_name));
+    descs.extend(gauge_vec.desc().into_iter().cloned());
+    gauge_vec
+}
+
+/// Initializes a GaugeVec metric aggregating all the diskpools of a node with the provided metric
+/// name, description and descriptors.
+fn init_diskpool_node_gauge_vec(
+    metric_name: &str,
+    metric_desc: &str,
+    descs: &mut Vec<Desc>,
+) -> GaugeVec {
+    let opts = Opts::new(metric_name, metric_desc)
+        .subsystem("diskpool_node")
+        .variable_labels(vec!["node".to_string()]);
+    let gauge_vec = GaugeVec::new(opts, &["node"])
+        .unwrap_or_else(|_| panic!("Unable to create gauge metric type for {}", metric_name));
+    descs.extend(gauge_vec.desc().into_iter().cloned());
+    gauge_vec
+}
+
+/// Initializes a GaugeVec metric for replica with the provided metric name, description and
+/// descriptors.
+fn init_replica_gauge_vec(metric_name: &str, metric_desc: &str, descs: &mut Vec<Desc>) -> GaugeVec {
+    let opts = Opts::new(metric_name, metric_desc)
+        .subsystem("replica")
+        .variable_labels(vec![
+            "node".to_string(),
+            "name".to_string(),
+            "pv_name".to_string(),
+        ]);
+    let gauge_vec = GaugeVec::new(opts, &["node", "name", "pv_name"])
+        .unwrap_or_else(|_| panic!("Unable to create gauge metric type for {}", metric_name));
+    descs.extend(gauge_vec.desc().into_iter().cloned());
+    gauge_vec
+}
+
+/// Initializes a CounterVec metric for diskpool with the provided metric name, description and
+/// descriptors.
+fn init_diskpool_counter_vec(
+    metric_name: &str,
+    metric_desc: &str,
+    descs: &mut Vec<Desc>,
+) -> CounterVec {
+    let opts = Opts::new(metric_name, metric_desc)
+        .subsystem("diskpool")
+        .variable_labels(vec!["node".to_string(), "name".to_string()]);
+    let counter_vec = CounterVec::new(opts, &["node", "name"])
+        .unwrap_or_else(|_| panic!("Unable to create counter metric type for {}", metric_name));
+    descs.extend(counter_vec.desc().into_iter().cloned());
+    counter_vec
+}
+
+/// Initializes a CounterVec metric for volume with the provided metric name, description and
+/// descriptors.
+fn init_volume_counter_vec(
+    metric_name: &str,
+    metric_desc: &str,
+    descs: &mut Vec<Desc>,
+) -> CounterVec {
+    let opts = Opts::new(metric_name, metric_desc)
+        .subsystem("volume")
+        .variable_labels(vec!["node".to_string(), "pv_name".to_string()]);
+    let counter_vec = CounterVec::new(opts, &["node", "pv_name"])
+        .unwrap_or_else(|_| panic!("Unable to create counter metric type for {}", metric_name));
+    descs.extend(counter_vec.desc().into_iter().cloned());
+    counter_vec
+}
+
+/// Initializes a CounterVec metric for replica with the provided metric name, description and
+/// descriptors.
+fn init_replica_counter_vec(
+    metric_name: &str,
+    metric_desc: &str,
+    descs: &mut Vec<Desc>,
+) -> CounterVec {
+    let opts = Opts::new(metric_name, metric_desc)
+        .subsystem("replica")
+        .variable_labels(vec![
+            "node".to_string(),
+            "name".to_string(),
+            "pv_name".to_string(),
+        ]);
+    let counter_vec = CounterVec::new(opts, &["node", "name", "pv_name"])
+        .unwrap_or_else(|_| panic!("Unable to create counter metric type for {}", metric_name));
+    descs.extend(counter_vec.desc().into_iter().cloned());
+    counter_vec
+}
+
+/// Initializes a latency histogram metric with the provided options and descriptors.
+fn init_latency_histogram_vec(opts: Opts, descs: &mut Vec<Desc>) -> LatencyHistogramVec {
+    let metric_name = opts.name.clone();
+    let histogram_vec = LatencyHistogramVec::new(opts)
+        .unwrap_or_else(|_| panic!("Unable to create histogram metric type for {}", metric_name));
+    descs.push(histogram_vec.desc().clone());
+    histogram_vec
+}
+
+/// Initializes a latency histogram metric for diskpool with the provided metric name,
+/// description and descriptors.
+fn init_diskpool_latency_histogram_vec(
+    metric_name: &str,
+    metric_desc: &str,
+    descs: &mut Vec<Desc>,
+) -> LatencyHistogramVec {
+    let opts = Opts::new(metric_name, metric_desc)
+        .subsystem("diskpool")
+        .variable_labels(vec!["node".to_string(), "name".to_string()]);
+    init_latency_histogram_vec(opts, descs)
+}
+
+/// Initializes a latency histogram metric for volume with the provided metric name,
+/// description and descriptors.
+fn init_volume_latency_histogram_vec(
+    metric_name: &str,
+    metric_desc: &str,
+    descs: &mut Vec<Desc>,
+) -> LatencyHistogramVec {
+    let opts = Opts::new(metric_name, metric_desc)
+        .subsystem("volume")
+        .variable_labels(vec!["node".to_string(), "pv_name".to_string()]);
+    init_latency_histogram_vec(opts, descs)
+}
+
+/// Initializes a latency histogram metric for replica with the provided metric name,
+/// description and descriptors.
+fn init_replica_latency_histogram_vec(
+    metric_name: &str,
+    metric_desc: &str,
+    descs: &mut Vec<Desc>,
+) -> LatencyHistogramVec {
+    let opts = Opts::new(metric_name, metric_desc)
+        .subsystem("replica")
+        .variable_labels(vec![
+            "node".to_string(),
+            "name".to_string(),
+            "pv_name".to_string(),
+        ]);
+    init_latency_histogram_vec(opts, descs)
+}
+
+/// Initializes the Opts of a metric about the exporter itself with the provided metric name,
+/// description and labels.
+fn exporter_opts(metric_name: &str, metric_desc: &str, labels: &[&str]) -> Opts {
+    Opts::new(metric_name, metric_desc)
+        .namespace("mayastor")
+        .subsystem("exporter")
+        .variable_labels(labels.iter().map(|label| label.to_string()).collect())
+}
+
+/// Initializes a GaugeVec metric for the exporter with the provided metric name, description,
+/// labels and descriptors.
+fn init_exporter_gauge_vec(
+    metric_name: &str,
+    metric_desc: &str,
+    labels: &[&str],
+    descs: &mut Vec<Desc>,
+) -> GaugeVec {
+    let gauge_vec = GaugeVec::new(exporter_opts(metric_name, metric_desc, labels), labels)
+        .unwrap_or_else(|_| panic!("Unable to create gauge metric type for {}", metric_name));
+    descs.extend(gauge_vec.desc().into_iter().cloned());
+    gauge_vec
+}
+
+/// Initializes a CounterVec metric for the exporter with the provided metric name, description,
+/// labels and descriptors.
+fn init_exporter_counter_vec(
+    metric_name: &str,
+    metric_desc: &str,
+    labels: &[&str],
+    descs: &mut Vec<Desc>,
+) -> CounterVec {
+    let counter_vec = CounterVec::new(exporter_opts(metric_name, metric_desc, labels), labels)
+        .unwrap_or_else(|_| panic!("Unable to create counter metric type for {}", metric_name));
+    descs.extend(counter_vec.desc().into_iter().cloned());
+    counter_vec
+}
@@ -0,0 +1,120 @@
+use super::{init_volume_child_gauge_vec, init_volume_gauge_vec};
+use crate::cache::SharedCache;
+use prometheus::{
+    core::{Collector, Desc},
+    GaugeVec,
+};
+use std::fmt::Debug;
+use tracing::error;
+
+/// Collects the state and health of the nexuses of the node and of their children.
+#[derive(Clone, Debug)]
+pub(crate) struct NexusStatusCollector {
+    volume_nexus_status: GaugeVec,
+    volume_nexus_ana_state: GaugeVec,
+    volume_nexus_child_count: GaugeVec,
+    volume_nexus_child_status: GaugeVec,
+    cache: SharedCache,
+    descs: Vec<Desc>,
+}
+
+impl NexusStatusCollector {
+    /// Initialize all the metrics to be defined for nexus status collector.
+    pub fn new(cache: SharedCache) -> Self {
+        let mut descs = Vec::new();
+        let volume_nexus_status = init_volume_gauge_vec(
+            "nexus_status",
+            "Status of the nexus of the volume",
+            &mut descs,
+        );
+        let volume_nexus_ana_state = init_volume_gauge_vec(
+            "nexus_ana_state",
+            "NVMe-oF ANA state of the nexus of the volume",
+            &mut descs,
+        );
+        let volume_nexus_child_count = init_volume_gauge_vec(
+            "nexus_child_count",
+            "Number of children of the nexus of the volume",
+            &mut descs,
+        );
+        let volume_nexus_child_status = init_volume_child_gauge_vec(
+            "nexus_child_status",
+            "Status of the child of the nexus of the volume",
+            &["reason"],
+            &mut descs,
+        );
+
+        Self {
+            volume_nexus_status,
+            volume_nexus_ana_state,
+            volume_nexus_child_count,
+            volume_nexus_child_status,
+            cache,
+            descs,
+        }
+    }
+}
+
+impl Collector for NexusStatusCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        self.descs.iter().collect()
+    }
+
+    fn collect(&self) -> Vec<prometheus::proto::MetricFamily> {
+        let cache = match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(error) => {
+                error!(%error,"Error while getting cache resource");
+                return Vec::new();
+            }
+        };
+        let mut metric_family = Vec::new();
+        for (node_name, cache_deref) in cache.nodes() {
+            for nexus in &cache_deref.nexus().nexuses {
+                let pv_name = format!("pvc-{}", nexus.name());
+                let labels = [node_name.as_str(), pv_name.as_str()];
+
+                for (gauge_vec, value) in [
+                    (&self.volume_nexus_status, nexus.state()),
+                    (&self.volume_nexus_ana_state, nexus.ana_state()),
+                    (
+                        &self.volume_nexus_child_count,
+                        nexus.children().len() as u64,
+                    ),
+                ] {
+                    let nexus_gauge = match gauge_vec.get_metric_with_label_values(&labels) {
+                        Ok(nexus_gauge) => nexus_gauge,
+                        Err(error) => {
+                            error!(%error, "Error while creating nexus status gauge with label values");
+                            return metric_family;
+                        }
+                    };
+                    nexus_gauge.set(value as f64);
+                    let mut metric_vec = nexus_gauge.collect();
+                    metric_family.extend(metric_vec.pop());
+                }
+
+                for child in nexus.children() {
+                    let volume_nexus_child_status = match self
+                        .volume_nexus_child_status
+                        .get_metric_with_label_values(&[
+                            node_name.as_str(),
+                            pv_name.as_str(),
+                            child.uri().as_str(),
+                            child.reason().as_str(),
+                        ]) {
+                        Ok(volume_nexus_child_status) => volume_nexus_child_status,
+                        Err(error) => {
+                            error!(%error, "Error while creating volume_nexus_child_status gauge with label values");
+                            return metric_family;
+                        }
+                    };
+                    volume_nexus_child_status.set(child.state() as f64);
+                    let mut metric_vec = volume_nexus_child_status.collect();
+                    metric_family.extend(metric_vec.pop());
+                }
+            }
+        }
+        metric_family
+    }
+}
@@ -0,0 +1,360 @@
+use super::{
+    exemplar::with_counter_exemplar, init_volume_counter_vec, init_volume_gauge_vec,
+    init_volume_latency_histogram_vec, latency::LatencyHistogramVec,
+};
+use crate::cache::SharedCache;
+use prometheus::{
+    core::{Collector, Desc},
+    CounterVec, GaugeVec,
+};
+use std::fmt::Debug;
+use tracing::error;
+
+/// Collects Nexus IoStat metrics from cache.
+#[derive(Clone, Debug)]
+pub(crate) struct NexusIoStatsCollector {
+    nexus_bytes_read: GaugeVec,
+    nexus_num_read_ops: GaugeVec,
+    nexus_bytes_written: GaugeVec,
+    nexus_num_write_ops: GaugeVec,
+    nexus_read_latency_us: GaugeVec,
+    nexus_write_latency_us: GaugeVec,
+    nexus_bytes_read_total: CounterVec,
+    nexus_num_read_ops_total: CounterVec,
+    nexus_bytes_written_total: CounterVec,
+    nexus_num_write_ops_total: CounterVec,
+    nexus_read_latency_us_total: CounterVec,
+    nexus_write_latency_us_total: CounterVec,
+    nexus_read_latency: LatencyHistogramVec,
+    nexus_write_latency: LatencyHistogramVec,
+    nexus_avg_read_latency: GaugeVec,
+    nexus_avg_write_latency: GaugeVec,
+    nexus_num_unmap_ops_total: CounterVec,
+    nexus_bytes_unmapped_total: CounterVec,
+    nexus_read_iops: GaugeVec,
+    nexus_write_iops: GaugeVec,
+    nexus_read_throughput: GaugeVec,
+    nexus_write_throughput: GaugeVec,
+    nexus_avg_read_size: GaugeVec,
+    nexus_avg_write_size: GaugeVec,
+    nexus_read_ratio: GaugeVec,
+    cache: SharedCache,
+    legacy_iostat_gauges: bool,
+    descs: Vec<Desc>,
+}
+
+impl NexusIoStatsCollector {
+    /// Initialize all the metrics to be defined for nexus iostat collector, the cumulative io
+    /// stats also being exported as the legacy gauges if enabled.
+    pub fn new(cache: SharedCache, legacy_iostat_gauges: bool) -> Self {
+        let mut descs = Vec::new();
+
+        let nexus_bytes_read =
+            init_volume_gauge_vec("bytes_read", "Total bytes read from the volume", &mut descs);
+        let nexus_num_read_ops = init_volume_gauge_vec(
+            "num_read_ops",
+            "Number of read operations on the volume",
+            &mut descs,
+        );
+        let nexus_bytes_written = init_volume_gauge_vec(
+            "bytes_written",
+            "Total bytes written on the volume",
+            &mut descs,
+        );
+        let nexus_num_write_ops = init_volume_gauge_vec(
+            "num_write_ops",
+            "Number of write operations on the volume",
+            &mut descs,
+        );
+        let nexus_read_latency_us = init_volume_gauge_vec(
+            "read_latency_us",
+            "Total read latency on the volume in usec",
+            &mut descs,
+        );
+        let nexus_write_latency_us = init_volume_gauge_vec(
+            "write_latency_us",
+            "Total write latency on the volume in usec",
+            &mut descs,
+        );
+        let nexus_bytes_read_total = init_volume_counter_vec(
+            "bytes_read_total",
+            "Total bytes read from the volume",
+            &mut descs,
+        );
+        let nexus_num_read_ops_total = init_volume_counter_vec(
+            "num_read_ops_total",
+            "Total number of read operations on the volume",
+            &mut descs,
+        );
+        let nexus_bytes_written_total = init_volume_counter_vec(
+            "bytes_written_total",
+            "Total bytes written on the volume",
+            &mut descs,
+        );
+        let nexus_num_write_ops_total = init_volume_counter_vec(
+            "num_write_ops_total",
+            "Total number of write operations on the volume",
+            &mut descs,
+        );
+        let nexus_read_latency_us_total = init_volume_counter_vec(
+            "read_latency_us_total",
+            "Total read latency on the volume in usec",
+            &mut descs,
+        );
+        let nexus_write_latency_us_total = init_volume_counter_vec(
+            "write_latency_us_total",
+            "Total write latency on the volume in usec",
+            &mut descs,
+        );
+        let nexus_read_latency = init_volume_latency_histogram_vec(
+            "read_latency_seconds",
+            "Read latency on the volume in seconds, derived from the average latency of each polling interval",
+            &mut descs,
+        );
+        let nexus_write_latency = init_volume_latency_histogram_vec(
+            "write_latency_seconds",
+            "Write latency on the volume in seconds, derived from the average latency of each polling interval",
+            &mut descs,
+        );
+        let nexus_avg_read_latency = init_volume_gauge_vec(
+            "avg_read_latency_seconds",
+            "Average read latency per operation on the volume during the last polling interval with io",
+            &mut descs,
+        );
+        let nexus_avg_write_latency = init_volume_gauge_vec(
+            "avg_write_latency_seconds",
+            "Average write latency per operation on the volume during the last polling interval with io",
+            &mut descs,
+        );
+        let nexus_num_unmap_ops_total = init_volume_counter_vec(
+            "num_unmap_ops_total",
+            "Total number of unmap operations on the volume",
+            &mut descs,
+        );
+        let nexus_bytes_unmapped_total = init_volume_counter_vec(
+            "bytes_unmapped_total",
+            "Total bytes unmapped on the volume",
+            &mut descs,
+        );
+        let nexus_read_iops = init_volume_gauge_vec(
+            "read_iops",
+            "Read operations per second on the volume during the last polling interval",
+            &mut descs,
+        );
+        let nexus_write_iops = init_volume_gauge_vec(
+            "write_iops",
+            "Write operations per second on the volume during the last polling interval",
+            &mut descs,
+        );
+        let nexus_read_throughput = init_volume_gauge_vec(
+            "read_throughput_bytes_per_second",
+            "Bytes read per second from the volume during the last polling interval",
+            &mut descs,
+        );
+        let nexus_write_throughput = init_volume_gauge_vec(
+            "write_throughput_bytes_per_second",
+            "Bytes written per second on the volume during the last polling interval",
+            &mut descs,
+        );
+        let nexus_avg_read_size = init_volume_gauge_vec(
+            "avg_read_size_bytes",
+            "Average size of the read operations on the volume during the last polling interval with reads",
+            &mut descs,
+        );
+        let nexus_avg_write_size = init_volume_gauge_vec(
+            "avg_write_size_bytes",
+            "Average size of the write operations on the volume during the last polling interval with writes",
+            &mut descs,
+        );
+        let nexus_read_ratio = init_volume_gauge_vec(
+            "read_ratio",
+            "Share of the read operations in the read and write operations on the volume during the last polling interval with io",
+            &mut descs,
+        );
+
+        Self {
+            nexus_bytes_read,
+            nexus_num_read_ops,
+            nexus_bytes_written,
+            nexus_num_write_ops,
+            nexus_read_latency_us,
+            nexus_write_latency_us,
+            nexus_bytes_read_total,
+            nexus_num_read_ops_total,
+            nexus_bytes_written_total,
+            nexus_num_write_ops_total,
+            nexus_read_latency_us_total,
+            nexus_write_latency_us_total,
+            nexus_read_latency,
+            nexus_write_latency,
+            nexus_avg_read_latency,
+            nexus_avg_write_latency,
+            nexus_num_unmap_ops_total,
+            nexus_bytes_unmapped_total,
+            nexus_read_iops,
+            nexus_write_iops,
+            nexus_read_throughput,
+            nexus_write_throughput,
+            nexus_avg_read_size,
+            nexus_avg_write_size,
+            nexus_read_ratio,
+            cache,
+            legacy_iostat_gauges,
+            descs,
+        }
+    }
+}
+
+impl Collector for NexusIoStatsCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        self.descs.iter().collect()
+    }
+
+    fn collect(&self) -> Vec<prometheus::proto::MetricFamily> {
+        let cache = match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(error) => {
+                error!(%error,"Error while getting cache resource");
+                return Vec::new();
+            }
+        };
+        let mut metric_family = Vec::new();
+        let mut read_latencies = Vec::new();
+        let mut write_latencies = Vec::new();
+        for (node_name, cache_deref) in cache.nodes() {
+            for nexus_stat in &cache_deref.nexus_iostat().nexus_stats {
+                let pv_name = "pvc-".to_string() + nexus_stat.name();
+                let label_values = vec![node_name.clone(), pv_name];
+                let labels = label_values.iter().map(String::as_str).collect::<Vec<_>>();
+
+                if let Some(counters) = cache_deref.nexus_counters().get(nexus_stat.name()) {
+                    for (counter_vec, counter) in [
+                        (&self.nexus_bytes_read_total, counters.bytes_read()),
+                        (&self.nexus_num_read_ops_total, counters.num_read_ops()),
+                        (&self.nexus_bytes_written_total, counters.bytes_written()),
+                        (&self.nexus_num_write_ops_total, counters.num_write_ops()),
+                        (
+                            &self.nexus_read_latency_us_total,
+                            counters.read_latency_us(),
+                        ),
+                        (
+                            &self.nexus_write_latency_us_total,
+                            counters.write_latency_us(),
+                        ),
+                    ] {
+                        let nexus_counter = match counter_vec.get_metric_with_label_values(&labels)
+                        {
+                            Ok(nexus_counter) => nexus_counter,
+                            Err(error) => {
+                                error!(%error, "Error while creating nexus iostat counter with label values");
+                                return metric_family;
+                            }
+                        };
+                        nexus_counter.inc_by(counter.value() as f64);
+                        let mut metric_vec = nexus_counter.collect();
+                        metric_family.extend(
+                            metric_vec
+                                .pop()
+                                .map(|family| with_counter_exemplar(family, counter)),
+                        );
+                    }
+                }
+
+                // Deprecated gauges of the cumulative io stats, kept for compatibility.
+                if self.legacy_iostat_gauges {
+                    for (gauge_vec, value) in [
+                        (&self.nexus_bytes_read, nexus_stat.bytes_read()),
+                        (&self.nexus_num_read_ops, nexus_stat.num_read_ops()),
+                        (&self.nexus_bytes_written, nexus_stat.bytes_written()),
+                        (&self.nexus_num_write_ops, nexus_stat.num_write_ops()),
+                        (&self.nexus_read_latency_us, nexus_stat.read_latency_us()),
+                        (&self.nexus_write_latency_us, nexus_stat.write_latency_us()),
+                    ] {
+                        let nexus_gauge = match gauge_vec.get_metric_with_label_values(&labels) {
+                            Ok(nexus_gauge) => nexus_gauge,
+                            Err(error) => {
+                                error!(%error, "Error while creating nexus iostat gauge with label values");
+                                return metric_family;
+                            }
+                        };
+                        nexus_gauge.set(value as f64);
+                        let mut metric_vec = nexus_gauge.collect();
+                        metric_family.extend(metric_vec.pop());
+                    }
+                }
+
+                if let Some(qos) = cache_deref.volumes_qos().get(nexus_stat.name()) {
+                    for (counter_vec, value) in [
+                        (&self.nexus_num_unmap_ops_total, qos.num_unmap_ops()),
+                        (&self.nexus_bytes_unmapped_total, qos.bytes_unmapped()),
+                    ] {
+                        let nexus_counter = match counter_vec.get_metric_with_label_values(&labels)
+                        {
+                            Ok(nexus_counter) => nexus_counter,
+                            Err(error) => {
+                                error!(%error, "Error while creating nexus unmap counter with label values");
+                                return metric_family;
+                            }
+                        };
+                        nexus_counter.inc_by(value as f64);
+                        let mut metric_vec = nexus_counter.collect();
+                        metric_family.extend(metric_vec.pop());
+                    }
+                    if let Some(rates) = qos.rates() {
+                        for (gauge_vec, value) in [
+                            (&self.nexus_read_iops, Some(rates.read_iops)),
+                            (&self.nexus_write_iops, Some(rates.write_iops)),
+                            (&self.nexus_read_throughput, Some(rates.read_throughput)),
+                            (&self.nexus_write_throughput, Some(rates.write_throughput)),
+                            (&self.nexus_avg_read_size, rates.avg_read_size),
+                            (&self.nexus_avg_write_size, rates.avg_write_size),
+                            (&self.nexus_read_ratio, rates.read_ratio),
+                        ] {
+                            let Some(value) = value else {
+                                continue;
+                            };
+                            let nexus_gauge = match gauge_vec.get_metric_with_label_values(&labels)
+                            {
+                                Ok(nexus_gauge) => nexus_gauge,
+                                Err(error) => {
+                                    error!(%error, "Error while creating nexus io rate gauge with label values");
+                                    return metric_family;
+                                }
+                            };
+                            nexus_gauge.set(value);
+                            let mut metric_vec = nexus_gauge.collect();
+                            metric_family.extend(metric_vec.pop());
+                        }
+                    }
+                }
+
+                let Some(latency) = cache_deref.nexus_latency().get(nexus_stat.name()) else {
+                    continue;
+                };
+                for (gauge_vec, histogram) in [
+                    (&self.nexus_avg_read_latency, latency.read()),
+                    (&self.nexus_avg_write_latency, latency.write()),
+                ] {
+                    let Some(interval_avg) = histogram.interval_avg() else {
+                        continue;
+                    };
+                    let nexus_avg_latency = match gauge_vec.get_metric_with_label_values(&labels) {
+                        Ok(nexus_avg_latency) => nexus_avg_latency,
+                        Err(error) => {
+                            error!(%error, "Error while creating nexus_avg_latency gauge with label values");
+                            return metric_family;
+                        }
+                    };
+                    nexus_avg_latency.set(interval_avg);
+                    let mut metric_vec = nexus_avg_latency.collect();
+                    metric_family.extend(metric_vec.pop());
+                }
+                read_latencies.push((label_values.clone(), latency.read()));
+                write_latencies.push((label_values, latency.write()));
+            }
+        }
+        metric_family.push(self.nexus_read_latency.metric_family(read_latencies));
+        metric_family.push(self.nexus_write_latency.metric_family(write_latencies));
+        metric_family
+    }
+}
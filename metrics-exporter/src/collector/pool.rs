@@ -0,0 +1,367 @@
+use crate::{
+    cache::SharedCache,
+    collector::{init_diskpool_gauge_vec, init_diskpool_node_gauge_vec},
+};
+use prometheus::{
+    core::{Collector, Desc},
+    GaugeVec,
+};
+use std::fmt::Debug;
+use tracing::error;
+
+/// Collects Pool capacity metrics from cache.
+#[derive(Clone, Debug)]
+pub(crate) struct PoolCapacityCollector {
+    pool_total_size: GaugeVec,
+    pool_used_size: GaugeVec,
+    pool_committed_size: GaugeVec,
+    pool_growth: GaugeVec,
+    pool_seconds_until_full: GaugeVec,
+    cache: SharedCache,
+    descs: Vec<Desc>,
+}
+
+impl PoolCapacityCollector {
+    /// Initialize all the metrics to be defined for pools capacity collector.
+    pub fn new(cache: SharedCache) -> Self {
+        let mut descs = Vec::new();
+        let pool_total_size = init_diskpool_gauge_vec(
+            "total_size_bytes",
+            "Total size of the pool in bytes",
+            &mut descs,
+        );
+        let pool_used_size = init_diskpool_gauge_vec(
+            "used_size_bytes",
+            "Used size of the pool in bytes",
+            &mut descs,
+        );
+        let pool_committed_size = init_diskpool_gauge_vec(
+            "committed_size_bytes",
+            "Committed size of the pool in bytes",
+            &mut descs,
+        );
+        let pool_growth = init_diskpool_gauge_vec(
+            "growth_bytes_per_second",
+            "Growth of the used size of the pool in bytes per second, over the capacity forecast window",
+            &mut descs,
+        );
+        let pool_seconds_until_full = init_diskpool_gauge_vec(
+            "seconds_until_full",
+            "Seconds until the pool is full at its current growth, only exported while the pool is growing",
+            &mut descs,
+        );
+
+        Self {
+            pool_total_size,
+            pool_used_size,
+            pool_committed_size,
+            pool_growth,
+            pool_seconds_until_full,
+            cache,
+            descs,
+        }
+    }
+}
+
+impl Collector for PoolCapacityCollector {
+    fn desc(&self) -> Vec<&prometheus::core::Desc> {
+        self.descs.iter().collect()
+    }
+
+    fn collect(&self) -> Vec<prometheus::proto::MetricFamily> {
+        let cache = match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(error) => {
+                error!(%error,"Error while getting cache resource");
+                return Vec::new();
+            }
+        };
+        let mut metric_family = Vec::new();
+        for (node_name, cache_deref) in cache.nodes() {
+            for pool in &cache_deref.pool().pools {
+                let pool_total_size = match self.pool_total_size.get_metric_with_label_values(&[
+                    node_name.clone().as_str(),
+                    pool.name().as_str(),
+                ]) {
+                    Ok(pool_total_size) => pool_total_size,
+                    Err(error) => {
+                        error!(%error, "Error while creating pool_total_size counter with label values");
+                        return metric_family;
+                    }
+                };
+                pool_total_size.set(pool.capacity() as f64);
+                let mut metric_vec = pool_total_size.collect();
+                metric_family.extend(metric_vec.pop());
+
+                let pool_used_size = match self.pool_used_size.get_metric_with_label_values(&[
+                    node_name.clone().as_str(),
+                    pool.name().as_str(),
+                ]) {
+                    Ok(pool_used_size) => pool_used_size,
+                    Err(error) => {
+                        error!(%error, "Error while creating pool_used_size counter with label values");
+                        return metric_family;
+                    }
+                };
+                pool_used_size.set(pool.used() as f64);
+                let mut metric_vec = pool_used_size.collect();
+                metric_family.extend(metric_vec.pop());
+
+                let pool_committed_size = match self
+                    .pool_committed_size
+                    .get_metric_with_label_values(&[
+                        node_name.clone().as_str(),
+                        pool.name().as_str(),
+                    ]) {
+                    Ok(pool_committed_size) => pool_committed_size,
+                    Err(error) => {
+                        error!(%error, "Error while creating pool_committed_size counter with label values");
+                        return metric_family;
+                    }
+                };
+                pool_committed_size.set(pool.committed() as f64);
+                let mut metric_vec = pool_committed_size.collect();
+                metric_family.extend(metric_vec.pop());
+
+                let Some(forecast) = cache_deref.pool_capacity_history().forecast(pool) else {
+                    continue;
+                };
+                let pool_growth = match self.pool_growth.get_metric_with_label_values(&[
+                    node_name.clone().as_str(),
+                    pool.name().as_str(),
+                ]) {
+                    Ok(pool_growth) => pool_growth,
+                    Err(error) => {
+                        error!(%error, "Error while creating pool_growth gauge with label values");
+                        return metric_family;
+                    }
+                };
+                pool_growth.set(forecast.growth_bytes_per_second);
+                let mut metric_vec = pool_growth.collect();
+                metric_family.extend(metric_vec.pop());
+
+                let Some(seconds_until_full) = forecast.seconds_until_full else {
+                    continue;
+                };
+                let pool_seconds_until_full = match self
+                    .pool_seconds_until_full
+                    .get_metric_with_label_values(&[
+                        node_name.clone().as_str(),
+                        pool.name().as_str(),
+                    ]) {
+                    Ok(pool_seconds_until_full) => pool_seconds_until_full,
+                    Err(error) => {
+                        error!(%error, "Error while creating pool_seconds_until_full gauge with label values");
+                        return metric_family;
+                    }
+                };
+                pool_seconds_until_full.set(seconds_until_full);
+                let mut metric_vec = pool_seconds_until_full.collect();
+                metric_family.extend(metric_vec.pop());
+            }
+        }
+        metric_family
+    }
+}
+
+/// Collects pool status info from cache.
+#[derive(Clone, Debug)]
+pub(crate) struct PoolStatusCollector {
+    pool_status: GaugeVec,
+    cache: SharedCache,
+    descs: Vec<Desc>,
+}
+
+impl PoolStatusCollector {
+    /// Initialize all the metrics to be defined for pools status collector.
+    pub fn new(cache: SharedCache) -> Self {
+        let mut descs = Vec::new();
+        let pool_status = init_diskpool_gauge_vec("status", "Status of the pool", &mut descs);
+        Self {
+            pool_status,
+            cache,
+            descs,
+        }
+    }
+}
+
+impl Collector for PoolStatusCollector {
+    fn desc(&self) -> Vec<&prometheus::core::Desc> {
+        self.descs.iter().collect()
+    }
+    fn collect(&self) -> Vec<prometheus::proto::MetricFamily> {
+        let cache = match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(error) => {
+                error!(%error,"Error while getting cache resource");
+                return Vec::new();
+            }
+        };
+        let mut metric_family = Vec::new();
+        for (node_name, cache_deref) in cache.nodes() {
+            for pool in &cache_deref.pool().pools {
+                let pool_status = match self.pool_status.get_metric_with_label_values(&[
+                    node_name.clone().as_str(),
+                    pool.name().as_str(),
+                ]) {
+                    Ok(pool_status) => pool_status,
+                    Err(error) => {
+                        error!(%error, "Error while creating pool_status counter with label values");
+                        return metric_family;
+                    }
+                };
+                pool_status.set(pool.state() as f64);
+                let mut metric_vec = pool_status.collect();
+                metric_family.extend(metric_vec.pop());
+            }
+        }
+        metric_family
+    }
+}
+
+/// Collects pool commitment metrics from cache, for each pool and aggregated for all the pools
+/// of the node.
+#[derive(Clone, Debug)]
+pub(crate) struct PoolCommitmentCollector {
+    pool_commitment_ratio: GaugeVec,
+    pool_free_after_commit: GaugeVec,
+    node_total_size: GaugeVec,
+    node_committed_size: GaugeVec,
+    node_commitment_ratio: GaugeVec,
+    node_free_after_commit: GaugeVec,
+    cache: SharedCache,
+    descs: Vec<Desc>,
+}
+
+impl PoolCommitmentCollector {
+    /// Initialize all the metrics to be defined for pools commitment collector.
+    pub fn new(cache: SharedCache) -> Self {
+        let mut descs = Vec::new();
+        let pool_commitment_ratio = init_diskpool_gauge_vec(
+            "commitment_ratio",
+            "Ratio of the committed size to the total size of the pool",
+            &mut descs,
+        );
+        let pool_free_after_commit = init_diskpool_gauge_vec(
+            "free_after_commit_bytes",
+            "Total size minus committed size of the pool in bytes, negative when overcommitted",
+            &mut descs,
+        );
+        let node_total_size = init_diskpool_node_gauge_vec(
+            "total_size_bytes",
+            "Total size of all the pools of the node in bytes",
+            &mut descs,
+        );
+        let node_committed_size = init_diskpool_node_gauge_vec(
+            "committed_size_bytes",
+            "Committed size of all the pools of the node in bytes",
+            &mut descs,
+        );
+        let node_commitment_ratio = init_diskpool_node_gauge_vec(
+            "commitment_ratio",
+            "Ratio of the committed size to the total size of all the pools of the node",
+            &mut descs,
+        );
+        let node_free_after_commit = init_diskpool_node_gauge_vec(
+            "free_after_commit_bytes",
+            "Total size minus committed size of all the pools of the node in bytes, negative when overcommitted",
+            &mut descs,
+        );
+
+        Self {
+            pool_commitment_ratio,
+            pool_free_after_commit,
+            node_total_size,
+            node_committed_size,
+            node_commitment_ratio,
+            node_free_after_commit,
+            cache,
+            descs,
+        }
+    }
+}
+
+impl Collector for PoolCommitmentCollector {
+    fn desc(&self) -> Vec<&prometheus::core::Desc> {
+        self.descs.iter().collect()
+    }
+
+    fn collect(&self) -> Vec<prometheus::proto::MetricFamily> {
+        let cache = match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(error) => {
+                error!(%error,"Error while getting cache resource");
+                return Vec::new();
+            }
+        };
+        let mut metric_family = Vec::new();
+        for (node_name, cache_deref) in cache.nodes() {
+            let pools = &cache_deref.pool().pools;
+            for pool in pools {
+                let labels = [node_name.as_str(), pool.name().as_str()];
+                let free_after_commit = pool.capacity() as f64 - pool.committed() as f64;
+                let pool_free_after_commit = match self
+                    .pool_free_after_commit
+                    .get_metric_with_label_values(&labels)
+                {
+                    Ok(pool_free_after_commit) => pool_free_after_commit,
+                    Err(error) => {
+                        error!(%error, "Error while creating pool_free_after_commit gauge with label values");
+                        return metric_family;
+                    }
+                };
+                pool_free_after_commit.set(free_after_commit);
+                let mut metric_vec = pool_free_after_commit.collect();
+                metric_family.extend(metric_vec.pop());
+
+                if pool.capacity() == 0 {
+                    continue;
+                }
+                let pool_commitment_ratio = match self
+                    .pool_commitment_ratio
+                    .get_metric_with_label_values(&labels)
+                {
+                    Ok(pool_commitment_ratio) => pool_commitment_ratio,
+                    Err(error) => {
+                        error!(%error, "Error while creating pool_commitment_ratio gauge with label values");
+                        return metric_family;
+                    }
+                };
+                pool_commitment_ratio.set(pool.committed() as f64 / pool.capacity() as f64);
+                let mut metric_vec = pool_commitment_ratio.collect();
+                metric_family.extend(metric_vec.pop());
+            }
+
+            if pools.is_empty() {
+                continue;
+            }
+            let total_size = pools.iter().map(|pool| pool.capacity() as f64).sum::<f64>();
+            let committed_size = pools
+                .iter()
+                .map(|pool| pool.committed() as f64)
+                .sum::<f64>();
+            let mut node_metrics = vec![
+                (&self.node_total_size, total_size),
+                (&self.node_committed_size, committed_size),
+                (&self.node_free_after_commit, total_size - committed_size),
+            ];
+            if total_size > 0.0 {
+                node_metrics.push((&self.node_commitment_ratio, committed_size / total_size));
+            }
+            for (gauge_vec, value) in node_metrics {
+                let node_gauge = match gauge_vec.get_metric_with_label_values(&[node_name.as_str()])
+                {
+                    Ok(node_gauge) => node_gauge,
+                    Err(error) => {
+                        error!(%error, "Error while creating node commitment gauge with label values");
+                        return metric_family;
+                    }
+                };
+                node_gauge.set(value);
+                let mut metric_vec = node_gauge.collect();
+                metric_family.extend(metric_vec.pop());
+            }
+        }
+        metric_family
+    }
+}
@@ -0,0 +1,248 @@
+use super::{
+    exemplar::with_counter_exemplar, init_diskpool_counter_vec, init_diskpool_gauge_vec,
+    init_diskpool_latency_histogram_vec, latency::LatencyHistogramVec,
+};
+use crate::cache::SharedCache;
+use prometheus::{
+    core::{Collector, Desc},
+    CounterVec, GaugeVec,
+};
+use std::fmt::Debug;
+use tracing::error;
+
+/// Collects Pool IoStat metrics from cache.
+#[derive(Clone, Debug)]
+pub(crate) struct PoolIoStatsCollector {
+    pool_bytes_read: GaugeVec,
+    pool_num_read_ops: GaugeVec,
+    pool_bytes_written: GaugeVec,
+    pool_num_write_ops: GaugeVec,
+    pool_read_latency_us: GaugeVec,
+    pool_write_latency_us: GaugeVec,
+    pool_bytes_read_total: CounterVec,
+    pool_num_read_ops_total: CounterVec,
+    pool_bytes_written_total: CounterVec,
+    pool_num_write_ops_total: CounterVec,
+    pool_read_latency_us_total: CounterVec,
+    pool_write_latency_us_total: CounterVec,
+    pool_read_latency: LatencyHistogramVec,
+    pool_write_latency: LatencyHistogramVec,
+    pool_avg_read_latency: GaugeVec,
+    pool_avg_write_latency: GaugeVec,
+    cache: SharedCache,
+    legacy_iostat_gauges: bool,
+    descs: Vec<Desc>,
+}
+
+/// Initialize all the metrics to be defined for pools iostat collector.
+impl PoolIoStatsCollector {
+    /// Initialize all the metrics to be defined for pools iostat collector, the cumulative io
+    /// stats also being exported as the legacy gauges if enabled.
+    pub fn new(cache: SharedCache, legacy_iostat_gauges: bool) -> Self {
+        let mut descs = Vec::new();
+
+        let pool_bytes_read =
+            init_diskpool_gauge_vec("bytes_read", "Total bytes read on the pool", &mut descs);
+        let pool_num_read_ops = init_diskpool_gauge_vec(
+            "num_read_ops",
+            "Number of read operations on the pool",
+            &mut descs,
+        );
+        let pool_bytes_written = init_diskpool_gauge_vec(
+            "bytes_written",
+            "Total bytes written on the pool",
+            &mut descs,
+        );
+        let pool_num_write_ops = init_diskpool_gauge_vec(
+            "num_write_ops",
+            "Number of write operations on the pool",
+            &mut descs,
+        );
+        let pool_read_latency_us = init_diskpool_gauge_vec(
+            "read_latency_us",
+            "Total read latency on the pool in usec",
+            &mut descs,
+        );
+        let pool_write_latency_us = init_diskpool_gauge_vec(
+            "write_latency_us",
+            "Total write latency on the pool in usec",
+            &mut descs,
+        );
+        let pool_bytes_read_total = init_diskpool_counter_vec(
+            "bytes_read_total",
+            "Total bytes read from the pool",
+            &mut descs,
+        );
+        let pool_num_read_ops_total = init_diskpool_counter_vec(
+            "num_read_ops_total",
+            "Total number of read operations on the pool",
+            &mut descs,
+        );
+        let pool_bytes_written_total = init_diskpool_counter_vec(
+            "bytes_written_total",
+            "Total bytes written on the pool",
+            &mut descs,
+        );
+        let pool_num_write_ops_total = init_diskpool_counter_vec(
+            "num_write_ops_total",
+            "Total number of write operations on the pool",
+            &mut descs,
+        );
+        let pool_read_latency_us_total = init_diskpool_counter_vec(
+            "read_latency_us_total",
+            "Total read latency on the pool in usec",
+            &mut descs,
+        );
+        let pool_write_latency_us_total = init_diskpool_counter_vec(
+            "write_latency_us_total",
+            "Total write latency on the pool in usec",
+            &mut descs,
+        );
+        let pool_read_latency = init_diskpool_latency_histogram_vec(
+            "read_latency_seconds",
+            "Read latency on the pool in seconds, derived from the average latency of each polling interval",
+            &mut descs,
+        );
+        let pool_write_latency = init_diskpool_latency_histogram_vec(
+            "write_latency_seconds",
+            "Write latency on the pool in seconds, derived from the average latency of each polling interval",
+            &mut descs,
+        );
+        let pool_avg_read_latency = init_diskpool_gauge_vec(
+            "avg_read_latency_seconds",
+            "Average read latency per operation on the pool during the last polling interval with io",
+            &mut descs,
+        );
+        let pool_avg_write_latency = init_diskpool_gauge_vec(
+            "avg_write_latency_seconds",
+            "Average write latency per operation on the pool during the last polling interval with io",
+            &mut descs,
+        );
+
+        Self {
+            pool_bytes_read,
+            pool_num_read_ops,
+            pool_bytes_written,
+            pool_num_write_ops,
+            pool_read_latency_us,
+            pool_write_latency_us,
+            pool_bytes_read_total,
+            pool_num_read_ops_total,
+            pool_bytes_written_total,
+            pool_num_write_ops_total,
+            pool_read_latency_us_total,
+            pool_write_latency_us_total,
+            pool_read_latency,
+            pool_write_latency,
+            pool_avg_read_latency,
+            pool_avg_write_latency,
+            cache,
+            legacy_iostat_gauges,
+            descs,
+        }
+    }
+}
+
+impl Collector for PoolIoStatsCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        self.descs.iter().collect()
+    }
+
+    fn collect(&self) -> Vec<prometheus::proto::MetricFamily> {
+        let cache = match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(error) => {
+                error!(%error,"Error while getting cache resource");
+                return Vec::new();
+            }
+        };
+        let mut metric_family = Vec::new();
+        let mut read_latencies = Vec::new();
+        let mut write_latencies = Vec::new();
+        for (node_name, cache_deref) in cache.nodes() {
+            for pool_stat in &cache_deref.pool_iostat().pool_stats {
+                let label_values = vec![node_name.clone(), pool_stat.name().clone()];
+                let labels = label_values.iter().map(String::as_str).collect::<Vec<_>>();
+
+                if let Some(counters) = cache_deref.pool_counters().get(pool_stat.name()) {
+                    for (counter_vec, counter) in [
+                        (&self.pool_bytes_read_total, counters.bytes_read()),
+                        (&self.pool_num_read_ops_total, counters.num_read_ops()),
+                        (&self.pool_bytes_written_total, counters.bytes_written()),
+                        (&self.pool_num_write_ops_total, counters.num_write_ops()),
+                        (&self.pool_read_latency_us_total, counters.read_latency_us()),
+                        (
+                            &self.pool_write_latency_us_total,
+                            counters.write_latency_us(),
+                        ),
+                    ] {
+                        let pool_counter = match counter_vec.get_metric_with_label_values(&labels) {
+                            Ok(pool_counter) => pool_counter,
+                            Err(error) => {
+                                error!(%error, "Error while creating pool iostat counter with label values");
+                                return metric_family;
+                            }
+                        };
+                        pool_counter.inc_by(counter.value() as f64);
+                        let mut metric_vec = pool_counter.collect();
+                        metric_family.extend(
+                            metric_vec
+                                .pop()
+                                .map(|family| with_counter_exemplar(family, counter)),
+                        );
+                    }
+                }
+
+                // Deprecated gauges of the cumulative io stats, kept for compatibility.
+                if self.legacy_iostat_gauges {
+                    for (gauge_vec, value) in [
+                        (&self.pool_bytes_read, pool_stat.bytes_read()),
+                        (&self.pool_num_read_ops, pool_stat.num_read_ops()),
+                        (&self.pool_bytes_written, pool_stat.bytes_written()),
+                        (&self.pool_num_write_ops, pool_stat.num_write_ops()),
+                        (&self.pool_read_latency_us, pool_stat.read_latency_us()),
+                        (&self.pool_write_latency_us, pool_stat.write_latency_us()),
+                    ] {
+                        let pool_gauge = match gauge_vec.get_metric_with_label_values(&labels) {
+                            Ok(pool_gauge) => pool_gauge,
+                            Err(error) => {
+                                error!(%error, "Error while creating pool iostat gauge with label values");
+                                return metric_family;
+                            }
+                        };
+                        pool_gauge.set(value as f64);
+                        let mut metric_vec = pool_gauge.collect();
+                        metric_family.extend(metric_vec.pop());
+                    }
+                }
+
+                let Some(latency) = cache_deref.pool_latency().get(pool_stat.name()) else {
+                    continue;
+                };
+                for (gauge_vec, histogram) in [
+                    (&self.pool_avg_read_latency, latency.read()),
+                    (&self.pool_avg_write_latency, latency.write()),
+                ] {
+                    let Some(interval_avg) = histogram.interval_avg() else {
+                        continue;
+                    };
+                    let pool_avg_latency = match gauge_vec.get_metric_with_label_values(&labels) {
+                        Ok(pool_avg_latency) => pool_avg_latency,
+                        Err(error) => {
+                            error!(%error, "Error while creating pool_avg_latency gauge with label values");
+                            return metric_family;
+                        }
+                    };
+                    pool_avg_latency.set(interval_avg);
+                    let mut metric_vec = pool_avg_latency.collect();
+                    metric_family.extend(metric_vec.pop());
+                }
+                read_latencies.push((label_values.clone(), latency.read()));
+                write_latencies.push((label_values, latency.write()));
+            }
+        }
+        metric_family.push(self.pool_read_latency.metric_family(read_latencies));
+        metric_family.push(self.pool_write_latency.metric_family(write_latencies));
+        metric_family
+    }
+}
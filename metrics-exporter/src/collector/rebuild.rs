@@ -0,0 +1,186 @@
+use super::{init_volume_child_gauge_vec, init_volume_gauge_vec};
+use crate::cache::SharedCache;
+use prometheus::{
+    core::{Collector, Desc},
+    GaugeVec,
+};
+use std::fmt::Debug;
+use tracing::error;
+
+/// Collects the progress of the rebuilds in progress on the nexuses of the node.
+#[derive(Clone, Debug)]
+pub(crate) struct RebuildCollector {
+    volume_rebuilds_in_progress: GaugeVec,
+    volume_rebuild_blocks: GaugeVec,
+    volume_rebuild_blocks_transferred: GaugeVec,
+    volume_rebuild_blocks_remaining: GaugeVec,
+    volume_rebuild_bytes_transferred: GaugeVec,
+    volume_rebuild_progress_percent: GaugeVec,
+    volume_rebuild_duration_seconds: GaugeVec,
+    volume_rebuild_throughput_bytes_per_second: GaugeVec,
+    cache: SharedCache,
+    descs: Vec<Desc>,
+}
+
+impl RebuildCollector {
+    /// Initialize all the metrics to be defined for rebuild collector.
+    pub fn new(cache: SharedCache) -> Self {
+        let mut descs = Vec::new();
+        let volume_rebuilds_in_progress = init_volume_gauge_vec(
+            "rebuilds_in_progress",
+            "Number of child rebuilds in progress on the nexus of the volume",
+            &mut descs,
+        );
+        let volume_rebuild_blocks = init_volume_child_gauge_vec(
+            "rebuild_blocks",
+            "Total number of blocks to be rebuilt on the child",
+            &["rebuild_type"],
+            &mut descs,
+        );
+        let volume_rebuild_blocks_transferred = init_volume_child_gauge_vec(
+            "rebuild_blocks_transferred",
+            "Number of blocks transferred to the child by the rebuild",
+            &["rebuild_type"],
+            &mut descs,
+        );
+        let volume_rebuild_blocks_remaining = init_volume_child_gauge_vec(
+            "rebuild_blocks_remaining",
+            "Number of blocks yet to be transferred to the child by the rebuild",
+            &["rebuild_type"],
+            &mut descs,
+        );
+        let volume_rebuild_bytes_transferred = init_volume_child_gauge_vec(
+            "rebuild_bytes_transferred",
+            "Number of bytes transferred to the child by the rebuild",
+            &["rebuild_type"],
+            &mut descs,
+        );
+        let volume_rebuild_progress_percent = init_volume_child_gauge_vec(
+            "rebuild_progress_percent",
+            "Progress of the rebuild of the child in percent",
+            &["rebuild_type"],
+            &mut descs,
+        );
+        let volume_rebuild_duration_seconds = init_volume_child_gauge_vec(
+            "rebuild_duration_seconds",
+            "Time elapsed since the rebuild of the child started",
+            &["rebuild_type"],
+            &mut descs,
+        );
+        let volume_rebuild_throughput_bytes_per_second = init_volume_child_gauge_vec(
+            "rebuild_throughput_bytes_per_second",
+            "Average rate at which bytes are transferred to the child since the rebuild started",
+            &["rebuild_type"],
+            &mut descs,
+        );
+
+        Self {
+            volume_rebuilds_in_progress,
+            volume_rebuild_blocks,
+            volume_rebuild_blocks_transferred,
+            volume_rebuild_blocks_remaining,
+            volume_rebuild_bytes_transferred,
+            volume_rebuild_progress_percent,
+            volume_rebuild_duration_seconds,
+            volume_rebuild_throughput_bytes_per_second,
+            cache,
+            descs,
+        }
+    }
+}
+
+impl Collector for RebuildCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        self.descs.iter().collect()
+    }
+
+    fn collect(&self) -> Vec<prometheus::proto::MetricFamily> {
+        let cache = match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(error) => {
+                error!(%error,"Error while getting cache resource");
+                return Vec::new();
+            }
+        };
+        let mut metric_family = Vec::new();
+        for (node_name, cache_deref) in cache.nodes() {
+            for nexus in &cache_deref.nexus().nexuses {
+                let pv_name = format!("pvc-{}", nexus.name());
+                let volume_rebuilds_in_progress = match self
+                    .volume_rebuilds_in_progress
+                    .get_metric_with_label_values(&[node_name.as_str(), pv_name.as_str()])
+                {
+                    Ok(volume_rebuilds_in_progress) => volume_rebuilds_in_progress,
+                    Err(error) => {
+                        error!(%error, "Error while creating volume_rebuilds_in_progress gauge with label values");
+                        return metric_family;
+                    }
+                };
+                volume_rebuilds_in_progress.set(nexus.rebuilds() as f64);
+                let mut metric_vec = volume_rebuilds_in_progress.collect();
+                metric_family.extend(metric_vec.pop());
+
+                for rebuild in nexus.rebuild_stats() {
+                    let rebuild_type = if rebuild.is_partial() {
+                        "partial"
+                    } else {
+                        "full"
+                    };
+                    let labels = [
+                        node_name.as_str(),
+                        pv_name.as_str(),
+                        rebuild.child_uri().as_str(),
+                        rebuild_type,
+                    ];
+                    let duration = rebuild.duration();
+
+                    let mut values = vec![
+                        (&self.volume_rebuild_blocks, rebuild.blocks_total() as f64),
+                        (
+                            &self.volume_rebuild_blocks_transferred,
+                            rebuild.blocks_transferred() as f64,
+                        ),
+                        (
+                            &self.volume_rebuild_blocks_remaining,
+                            rebuild.blocks_remaining() as f64,
+                        ),
+                        (
+                            &self.volume_rebuild_bytes_transferred,
+                            rebuild.bytes_transferred() as f64,
+                        ),
+                        (
+                            &self.volume_rebuild_progress_percent,
+                            rebuild.progress() as f64,
+                        ),
+                    ];
+                    if let Some(duration) = duration {
+                        values.push((
+                            &self.volume_rebuild_duration_seconds,
+                            duration.as_secs_f64(),
+                        ));
+                        if !duration.is_zero() {
+                            values.push((
+                                &self.volume_rebuild_throughput_bytes_per_second,
+                                rebuild.bytes_transferred() as f64 / duration.as_secs_f64(),
+                            ));
+                        }
+                    }
+
+                    for (gauge_vec, value) in values {
+                        let rebuild_gauge = match gauge_vec.get_metric_with_label_values(&labels) {
+                            Ok(rebuild_gauge) => rebuild_gauge,
+                            Err(error) => {
+                                error!(%error, "Error while creating volume rebuild gauge with label values");
+                                return metric_family;
+                            }
+                        };
+                        rebuild_gauge.set(value);
+                        let mut metric_vec = rebuild_gauge.collect();
+                        metric_family.extend(metric_vec.pop());
+                    }
+                }
+            }
+        }
+        metric_family
+    }
+}
@@ -0,0 +1,238 @@
+use super::{
+    cluster::ClusterTotalsCollector,
+    disk::PoolDiskCollector,
+    exporter::ExporterHealthCollector,
+    filter::{drop_labels, limit_series},
+    nexus::NexusStatusCollector,
+    nexus_stat::NexusIoStatsCollector,
+    pool::{PoolCapacityCollector, PoolCommitmentCollector, PoolStatusCollector},
+    pool_stat::PoolIoStatsCollector,
+    rebuild::RebuildCollector,
+    replica_stat::ReplicaIoStatsCollector,
+    volume::VolumeRollupCollector,
+};
+use crate::{
+    cache::SharedCache,
+    config::{CollectorFamily, ExporterConfig},
+};
+use prometheus::{core::Collector, proto::MetricFamily, Registry};
+use std::collections::HashMap;
+use tracing::warn;
+
+/// Create collectors for all resources reading from the cache, along with their family and name.
+fn collectors(
+    cache: &SharedCache,
+    config: &ExporterConfig,
+) -> Vec<(CollectorFamily, &'static str, Box<dyn Collector>)> {
+    let legacy_iostat_gauges = config.legacy_iostat_gauges();
+    vec![
+        (
+            CollectorFamily::Pool,
+            "Pool capacity",
+            Box::new(PoolCapacityCollector::new(cache.clone())),
+        ),
+        (
+            CollectorFamily::Pool,
+            "Pool status",
+            Box::new(PoolStatusCollector::new(cache.clone())),
+        ),
+        (
+            CollectorFamily::Pool,
+            "Pool commitment",
+            Box::new(PoolCommitmentCollector::new(cache.clone())),
+        ),
+        (
+            CollectorFamily::PoolIostat,
+            "Pool IoStat",
+            Box::new(PoolIoStatsCollector::new(
+                cache.clone(),
+                legacy_iostat_gauges,
+            )),
+        ),
+        (
+            CollectorFamily::Disk,
+            "Pool disk",
+            Box::new(PoolDiskCollector::new(cache.clone())),
+        ),
+        (
+            CollectorFamily::Nexus,
+            "Nexus status",
+            Box::new(NexusStatusCollector::new(cache.clone())),
+        ),
+        (
+            CollectorFamily::NexusIostat,
+            "Nexus IoStat",
+            Box::new(NexusIoStatsCollector::new(
+                cache.clone(),
+                legacy_iostat_gauges,
+            )),
+        ),
+        (
+            CollectorFamily::ReplicaIostat,
+            "Replica IoStat",
+            Box::new(ReplicaIoStatsCollector::new(
+                cache.clone(),
+                legacy_iostat_gauges,
+            )),
+        ),
+        (
+            CollectorFamily::Volume,
+            "Volume rollup",
+            Box::new(VolumeRollupCollector::new(cache.clone())),
+        ),
+        (
+            CollectorFamily::Rebuild,
+            "Rebuild",
+            Box::new(RebuildCollector::new(cache.clone())),
+        ),
+        (
+            CollectorFamily::Exporter,
+            "Exporter health",
+            Box::new(ExporterHealthCollector::new(cache.clone())),
+        ),
+        (
+            CollectorFamily::Cluster,
+            "Cluster totals",
+            Box::new(ClusterTotalsCollector::new(cache.clone())),
+        ),
+    ]
+}
+
+/// Initializes the collectors of the enabled families in a new registry and gathers their
+/// metrics from the cached data, dropping the configured labels and the series beyond the limit.
+pub(crate) fn gather(cache: &SharedCache, config: &ExporterConfig) -> Vec<MetricFamily> {
+    // Create a new registry for prometheus.
+    let registry = Registry::default();
+    // Labels to be dropped from each metric, by metric name.
+    let mut dropped_labels = HashMap::new();
+    // Register the collectors of the enabled families to the registry.
+    for (family, name, collector) in collectors(cache, config) {
+        if !config.collector_enabled(family) {
+            continue;
+        }
+        let labels = config.drop_labels(family);
+        if !labels.is_empty() {
+            for desc in collector.desc() {
+                dropped_labels.insert(desc.fq_name.clone(), labels);
+            }
+        }
+        if let Err(error) = Registry::register(&registry, collector) {
+            warn!(%error, "{name} collector already registered");
+        }
+    }
+
+    // Internally calls collect() on all collectors, merging the families of the same name.
+    let mut metric_families = registry.gather();
+    // The series of a metric are only all in one family once merged, so that they can be
+    // aggregated.
+    for metric_family in &mut metric_families {
+        if let Some(labels) = dropped_labels.get(metric_family.get_name()) {
+            drop_labels(metric_family, labels);
+        }
+    }
+    if let Some(max_series) = config.max_series() {
+        let (total, dropped) = limit_series(&mut metric_families, max_series);
+        if dropped > 0 {
+            warn!(
+                total,
+                dropped, max_series, "Number of series exceeds the limit, dropping the excess"
+            );
+        }
+    }
+    metric_families
+}
+
+#[cfg(test)]
+mod tests {
+    use super::gather;
+    use crate::{
+        cache::{store_node_data, Cache},
+        client::fake::FakeIoEngine,
+        config::ExporterConfig,
+        Cli,
+    };
+    use clap::Parser;
+    use prometheus::proto::MetricFamily;
+    use rpc::v1::{pool, stats};
+
+    fn io_stats(name: &str, num_read_ops: u64) -> stats::IoStats {
+        stats::IoStats {
+            name: name.to_string(),
+            num_read_ops,
+            tick_rate: 1000000,
+            ..Default::default()
+        }
+    }
+
+    fn pool(name: &str, used: u64) -> pool::Pool {
+        pool::Pool {
+            name: name.to_string(),
+            capacity: 10737418240,
+            used,
+            ..Default::default()
+        }
+    }
+
+    /// Get the label names and values, along with the value of each metric of the family.
+    fn series<'a>(
+        metric_families: &'a [MetricFamily],
+        name: &str,
+    ) -> Vec<(Vec<(&'a str, &'a str)>, f64)> {
+        let metric_family = metric_families
+            .iter()
+            .find(|metric_family| metric_family.get_name() == name)
+            .unwrap();
+        metric_family
+            .get_metric()
+            .iter()
+            .map(|metric| {
+                let labels = metric
+                    .get_label()
+                    .iter()
+                    .map(|label| (label.get_name(), label.get_value()))
+                    .collect();
+                let value = match metric.has_counter() {
+                    true => metric.get_counter().get_value(),
+                    false => metric.get_gauge().get_value(),
+                };
+                (labels, value)
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_gather_drop_labels() {
+        let args = Cli::parse_from([
+            "metrics-exporter",
+            "--drop-labels=replica_iostat:name,pool:name",
+        ]);
+        let config = ExporterConfig::try_from(&args).unwrap();
+        let fake = FakeIoEngine {
+            pools: vec![pool("pool-1", 2147483648), pool("pool-2", 4294967296)],
+            replica_stats: ["replica-1", "replica-2"]
+                .into_iter()
+                .enumerate()
+                .map(|(index, replica)| stats::ReplicaIoStats {
+                    entity_id: Some("v".to_string()),
+                    stats: Some(io_stats(replica, 100 * (index as u64 + 1))),
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let client = fake.serve();
+        let cache = Cache::new_shared();
+        store_node_data(&client, &cache, "n", &config).await;
+
+        let metric_families = gather(&cache, &config);
+        // The counters of the replicas of the volume are summed into a single series.
+        assert_eq!(
+            series(&metric_families, "replica_num_read_ops_total"),
+            vec![(vec![("node", "n"), ("pv_name", "pvc-v")], 300.0)]
+        );
+        // The gauges of the pools of the node are averaged into a single series.
+        assert_eq!(
+            series(&metric_families, "diskpool_used_size_bytes"),
+            vec![(vec![("node", "n")], 3221225472.0)]
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use super::{
+    exemplar::with_counter_exemplar, init_replica_counter_vec, init_replica_gauge_vec,
+    init_replica_latency_histogram_vec, latency::LatencyHistogramVec,
+};
+use crate::cache::SharedCache;
+use prometheus::{
+    core::{Collector, Desc},
+    CounterVec, GaugeVec,
+};
+use std::fmt::Debug;
+use tracing::error;
+
+/// Collects Replica IoStat metrics from cache.
+#[derive(Clone, Debug)]
+pub(crate) struct ReplicaIoStatsCollector {
+    replica_bytes_read: GaugeVec,
+    replica_num_read_ops: GaugeVec,
+    replica_bytes_written: GaugeVec,
+    replica_num_write_ops: GaugeVec,
+    replica_read_latency_us: GaugeVec,
+    replica_write_latency_us: GaugeVec,
+    replica_bytes_read_total: CounterVec,
+    replica_num_read_ops_total: CounterVec,
+    replica_bytes_written_total: CounterVec,
+    replica_num_write_ops_total: CounterVec,
+    replica_read_latency_us_total: CounterVec,
+    replica_write_latency_us_total: CounterVec,
+    replica_read_latency: LatencyHistogramVec,
+    replica_write_latency: LatencyHistogramVec,
+    replica_avg_read_latency: GaugeVec,
+    replica_avg_write_latency: GaugeVec,
+    cache: SharedCache,
+    legacy_iostat_gauges: bool,
+    descs: Vec<Desc>,
+}
+
+/// Initialize all the metrics to be defined for replicas iostat collector.
+impl ReplicaIoStatsCollector {
+    /// Initialize all the metrics to be defined for replicas iostat collector, the cumulative io
+    /// stats also being exported as the legacy gauges if enabled.
+    pub fn new(cache: SharedCache, legacy_iostat_gauges: bool) -> Self {
+        let mut descs = Vec::new();
+
+        let replica_bytes_read =
+            init_replica_gauge_vec("bytes_read", "Total bytes read on the replica", &mut descs);
+        let replica_num_read_ops = init_replica_gauge_vec(
+            "num_read_ops",
+            "Number of read operations on the replica",
+            &mut descs,
+        );
+        let replica_bytes_written = init_replica_gauge_vec(
+            "bytes_written",
+            "Total bytes written on the replica",
+            &mut descs,
+        );
+        let replica_num_write_ops = init_replica_gauge_vec(
+            "num_write_ops",
+            "Number of write operations on the replica",
+            &mut descs,
+        );
+        let replica_read_latency_us = init_replica_gauge_vec(
+            "read_latency_us",
+            "Total read latency on the replica in usec",
+            &mut descs,
+        );
+        let replica_write_latency_us = init_replica_gauge_vec(
+            "write_latency_us",
+            "Total write latency on the replica in usec",
+            &mut descs,
+        );
+        let replica_bytes_read_total = init_replica_counter_vec(
+            "bytes_read_total",
+            "Total bytes read from the replica",
+            &mut descs,
+        );
+        let replica_num_read_ops_total = init_replica_counter_vec(
+            "num_read_ops_total",
+            "Total number of read operations on the replica",
+            &mut descs,
+        );
+        let replica_bytes_written_total = init_replica_counter_vec(
+            "bytes_written_total",
+            "Total bytes written on the replica",
+            &mut descs,
+        );
+        let replica_num_write_ops_total = init_replica_counter_vec(
+            "num_write_ops_total",
+            "Total number of write operations on the replica",
+            &mut descs,
+        );
+        let replica_read_latency_us_total = init_replica_counter_vec(
+            "read_latency_us_total",
+            "Total read latency on the replica in usec",
+            &mut descs,
+        );
+        let replica_write_latency_us_total = init_replica_counter_vec(
+            "write_latency_us_total",
+            "Total write latency on the replica in usec",
+            &mut descs,
+        );
+        let replica_read_latency = init_replica_latency_histogram_vec(
+            "read_latency_seconds",
+            "Read latency on the replica in seconds, derived from the average latency of each polling interval",
+            &mut descs,
+        );
+        let replica_write_latency = init_replica_latency_histogram_vec(
+            "write_latency_seconds",
+            "Write latency on the replica in seconds, derived from the average latency of each polling interval",
+            &mut descs,
+        );
+        let replica_avg_read_latency = init_replica_gauge_vec(
+            "avg_read_latency_seconds",
+            "Average read latency per operation on the replica during the last polling interval with io",
+            &mut descs,
+        );
+        let replica_avg_write_latency = init_replica_gauge_vec(
+            "avg_write_latency_seconds",
+            "Average write latency per operation on the replica during the last polling interval with io",
+            &mut descs,
+        );
+
+        Self {
+            replica_bytes_read,
+            replica_num_read_ops,
+            replica_bytes_written,
+            replica_num_write_ops,
+            replica_read_latency_us,
+            replica_write_latency_us,
+            replica_bytes_read_total,
+            replica_num_read_ops_total,
+            replica_bytes_written_total,
+            replica_num_write_ops_total,
+            replica_read_latency_us_total,
+            replica_write_latency_us_total,
+            replica_read_latency,
+            replica_write_latency,
+            replica_avg_read_latency,
+            replica_avg_write_latency,
+            cache,
+            legacy_iostat_gauges,
+            descs,
+        }
+    }
+}
+
+impl Collector for ReplicaIoStatsCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        self.descs.iter().collect()
+    }
+
+    fn collect(&self) -> Vec<prometheus::proto::MetricFamily> {
+        let cache = match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(error) => {
+                error!(%error,"Error while getting cache resource");
+                return Vec::new();
+            }
+        };
+        let mut metric_family = Vec::new();
+        let mut read_latencies = Vec::new();
+        let mut write_latencies = Vec::new();
+        for (node_name, cache_deref) in cache.nodes() {
+            for replica_stat in &cache_deref.replica_iostat().replica_stats {
+                let pv_name = format!("pvc-{}", replica_stat.entity_id());
+                let label_values = vec![node_name.clone(), replica_stat.name().clone(), pv_name];
+                let labels = label_values.iter().map(String::as_str).collect::<Vec<_>>();
+
+                if let Some(counters) = cache_deref.replica_counters().get(replica_stat.name()) {
+                    for (counter_vec, counter) in [
+                        (&self.replica_bytes_read_total, counters.bytes_read()),
+                        (&self.replica_num_read_ops_total, counters.num_read_ops()),
+                        (&self.replica_bytes_written_total, counters.bytes_written()),
+                        (&self.replica_num_write_ops_total, counters.num_write_ops()),
+                        (
+                            &self.replica_read_latency_us_total,
+                            counters.read_latency_us(),
+                        ),
+                        (
+                            &self.replica_write_latency_us_total,
+                            counters.write_latency_us(),
+                        ),
+                    ] {
+                        let replica_counter = match counter_vec
+                            .get_metric_with_label_values(&labels)
+                        {
+                            Ok(replica_counter) => replica_counter,
+                            Err(error) => {
+                                error!(%error, "Error while creating replica iostat counter with label values");
+                                return metric_family;
+                            }
+                        };
+                        replica_counter.inc_by(counter.value() as f64);
+                        let mut metric_vec = replica_counter.collect();
+                        metric_family.extend(
+                            metric_vec
+                                .pop()
+                                .map(|family| with_counter_exemplar(family, counter)),
+                        );
+                    }
+                }
+
+                // Deprecated gauges of the cumulative io stats, kept for compatibility.
+                if self.legacy_iostat_gauges {
+                    for (gauge_vec, value) in [
+                        (&self.replica_bytes_read, replica_stat.bytes_read()),
+                        (&self.replica_num_read_ops, replica_stat.num_read_ops()),
+                        (&self.replica_bytes_written, replica_stat.bytes_written()),
+                        (&self.replica_num_write_ops, replica_stat.num_write_ops()),
+                        (
+                            &self.replica_read_latency_us,
+                            replica_stat.read_latency_us(),
+                        ),
+                        (
+                            &self.replica_write_latency_us,
+                            replica_stat.write_latency_us(),
+                        ),
+                    ] {
+                        let replica_gauge = match gauge_vec.get_metric_with_label_values(&labels) {
+                            Ok(replica_gauge) => replica_gauge,
+                            Err(error) => {
+                                error!(%error, "Error while creating replica iostat gauge with label values");
+                                return metric_family;
+                            }
+                        };
+                        replica_gauge.set(value as f64);
+                        let mut metric_vec = replica_gauge.collect();
+                        metric_family.extend(metric_vec.pop());
+                    }
+                }
+
+                let Some(latency) = cache_deref.replica_latency().get(replica_stat.name()) else {
+                    continue;
+                };
+                for (gauge_vec, histogram) in [
+                    (&self.replica_avg_read_latency, latency.read()),
+                    (&self.replica_avg_write_latency, latency.write()),
+                ] {
+                    let Some(interval_avg) = histogram.interval_avg() else {
+                        continue;
+                    };
+                    let replica_avg_latency = match gauge_vec.get_metric_with_label_values(&labels)
+                    {
+                        Ok(replica_avg_latency) => replica_avg_latency,
+                        Err(error) => {
+                            error!(%error, "Error while creating replica_avg_latency gauge with label values");
+                            return metric_family;
+                        }
+                    };
+                    replica_avg_latency.set(interval_avg);
+                    let mut metric_vec = replica_avg_latency.collect();
+                    metric_family.extend(metric_vec.pop());
+                }
+                read_latencies.push((label_values.clone(), latency.read()));
+                write_latencies.push((label_values, latency.write()));
+            }
+        }
+        metric_family.push(self.replica_read_latency.metric_family(read_latencies));
+        metric_family.push(self.replica_write_latency.metric_family(write_latencies));
+        metric_family
+    }
+}
@@ -0,0 +1,342 @@
+use super::{init_volume_counter_vec, init_volume_gauge_vec};
+use crate::cache::SharedCache;
+use prometheus::{
+    core::{Collector, Desc},
+    CounterVec, GaugeVec,
+};
+use std::{collections::BTreeMap, fmt::Debug};
+use tracing::error;
+
+/// Rollup of the io stats of the replicas of a volume.
+#[derive(Debug, Default)]
+struct ReplicaRollup {
+    replicas: u64,
+    writing_replicas: u64,
+    bytes_written_increase: u64,
+}
+
+/// Collects volume metrics derived from the io stats of all the replicas of each volume, on all
+/// the cached nodes in cluster mode, and of the nexus of the volume when it is cached. The `node`
+/// label is the node of the nexus, or the node of the exporter when the nexus is on another node.
+#[derive(Clone, Debug)]
+pub(crate) struct VolumeRollupCollector {
+    volume_replica_count: GaugeVec,
+    volume_replica_writing_count: GaugeVec,
+    volume_replica_bytes_read_total: CounterVec,
+    volume_replica_bytes_written_total: CounterVec,
+    volume_replica_write_ratio: GaugeVec,
+    cache: SharedCache,
+    descs: Vec<Desc>,
+}
+
+impl VolumeRollupCollector {
+    /// Initialize all the metrics to be defined for volume rollup collector.
+    pub fn new(cache: SharedCache) -> Self {
+        let mut descs = Vec::new();
+        let volume_replica_count = init_volume_gauge_vec(
+            "replica_count",
+            "Number of replicas of the volume reporting io stats on the nodes of the exporter",
+            &mut descs,
+        );
+        let volume_replica_writing_count = init_volume_gauge_vec(
+            "replica_writing_count",
+            "Number of replicas of the volume on the nodes of the exporter which received writes during the last polling interval",
+            &mut descs,
+        );
+        let volume_replica_bytes_read_total = init_volume_counter_vec(
+            "replica_bytes_read_total",
+            "Total bytes read from the replicas of the volume on the nodes of the exporter, including the replicas which went away",
+            &mut descs,
+        );
+        let volume_replica_bytes_written_total = init_volume_counter_vec(
+            "replica_bytes_written_total",
+            "Total bytes written to the replicas of the volume on the nodes of the exporter, including the replicas which went away",
+            &mut descs,
+        );
+        let volume_replica_write_ratio = init_volume_gauge_vec(
+            "replica_write_ratio",
+            "Ratio of the bytes written to the replicas of the volume on the nodes of the exporter to the bytes written to the volume during the last polling interval, when the nexus is on one of these nodes",
+            &mut descs,
+        );
+
+        Self {
+            volume_replica_count,
+            volume_replica_writing_count,
+            volume_replica_bytes_read_total,
+            volume_replica_bytes_written_total,
+            volume_replica_write_ratio,
+            cache,
+            descs,
+        }
+    }
+}
+
+impl Collector for VolumeRollupCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        self.descs.iter().collect()
+    }
+
+    fn collect(&self) -> Vec<prometheus::proto::MetricFamily> {
+        let cache = match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(error) => {
+                error!(%error,"Error while getting cache resource");
+                return Vec::new();
+            }
+        };
+        let mut metric_family = Vec::new();
+        // The replicas of a volume are spread across the nodes, which are all cached in cluster
+        // mode.
+        let mut rollups = BTreeMap::<String, ReplicaRollup>::new();
+        for (_, cache_deref) in cache.nodes() {
+            for replica_stat in &cache_deref.replica_iostat().replica_stats {
+                let Some(counters) = cache_deref.replica_counters().get(replica_stat.name()) else {
+                    continue;
+                };
+                let rollup = rollups.entry(replica_stat.entity_id()).or_default();
+                rollup.replicas += 1;
+                if counters.bytes_written_increase() > 0 {
+                    rollup.writing_replicas += 1;
+                }
+                rollup.bytes_written_increase += counters.bytes_written_increase();
+            }
+        }
+        // The node of the exporter, unless it scrapes all the nodes of the cluster.
+        let exporter_node = match cache.nodes().count() {
+            1 => cache
+                .nodes()
+                .next()
+                .map(|(node_name, _)| node_name.as_str()),
+            _ => None,
+        };
+
+        for (entity_id, rollup) in rollups {
+            // The nexus of the volume is named after the volume uuid, as the replicas entity_id.
+            let nexus = cache.nodes().find_map(|(node_name, cache_deref)| {
+                let counters = cache_deref.nexus_counters().get(&entity_id)?;
+                Some((node_name.as_str(), counters.bytes_written_increase()))
+            });
+            let node_name = nexus
+                .map(|(node_name, _)| node_name)
+                .or(exporter_node)
+                .unwrap_or_default();
+            let pv_name = format!("pvc-{entity_id}");
+            let labels = [node_name, pv_name.as_str()];
+
+            for (gauge_vec, value) in [
+                (&self.volume_replica_count, rollup.replicas),
+                (&self.volume_replica_writing_count, rollup.writing_replicas),
+            ] {
+                let volume_gauge = match gauge_vec.get_metric_with_label_values(&labels) {
+                    Ok(volume_gauge) => volume_gauge,
+                    Err(error) => {
+                        error!(%error, "Error while creating volume rollup gauge with label values");
+                        return metric_family;
+                    }
+                };
+                volume_gauge.set(value as f64);
+                let mut metric_vec = volume_gauge.collect();
+                metric_family.extend(metric_vec.pop());
+            }
+
+            // Unlike the sum of the counters of the current replicas, these do not drop when a
+            // replica goes away.
+            let volume_counters = cache.volume_replica_counters().get(&entity_id);
+            for (counter_vec, counter) in volume_counters.into_iter().flat_map(|counters| {
+                [
+                    (&self.volume_replica_bytes_read_total, counters.bytes_read()),
+                    (
+                        &self.volume_replica_bytes_written_total,
+                        counters.bytes_written(),
+                    ),
+                ]
+            }) {
+                let volume_counter = match counter_vec.get_metric_with_label_values(&labels) {
+                    Ok(volume_counter) => volume_counter,
+                    Err(error) => {
+                        error!(%error, "Error while creating volume rollup counter with label values");
+                        return metric_family;
+                    }
+                };
+                volume_counter.inc_by(counter.value() as f64);
+                let mut metric_vec = volume_counter.collect();
+                metric_family.extend(metric_vec.pop());
+            }
+
+            // Without the writes of the nexus, the writes of the replicas cannot be compared.
+            let Some((_, nexus_written_increase @ 1 ..)) = nexus else {
+                continue;
+            };
+            let volume_replica_write_ratio = match self
+                .volume_replica_write_ratio
+                .get_metric_with_label_values(&labels)
+            {
+                Ok(volume_replica_write_ratio) => volume_replica_write_ratio,
+                Err(error) => {
+                    error!(%error, "Error while creating volume_replica_write_ratio gauge with label values");
+                    return metric_family;
+                }
+            };
+            volume_replica_write_ratio
+                .set(rollup.bytes_written_increase as f64 / nexus_written_increase as f64);
+            let mut metric_vec = volume_replica_write_ratio.collect();
+            metric_family.extend(metric_vec.pop());
+        }
+        metric_family
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VolumeRollupCollector;
+    use crate::{
+        cache::{store_node_data, Cache, SharedCache},
+        client::fake::FakeIoEngine,
+        config::ExporterConfig,
+        Cli,
+    };
+    use clap::Parser;
+    use prometheus::{core::Collector, proto::MetricFamily};
+    use rpc::v1::stats;
+
+    fn io_stats(name: &str, bytes_written: u64) -> stats::IoStats {
+        stats::IoStats {
+            name: name.to_string(),
+            bytes_written,
+            tick_rate: 1000000,
+            ..Default::default()
+        }
+    }
+
+    /// Fake io-engine of a node with a replica of the volume "v", and its nexus when given.
+    fn fake_node(replica: &str, nexus: bool, bytes_written: u64) -> FakeIoEngine {
+        FakeIoEngine {
+            nexus_stats: match nexus {
+                true => vec![io_stats("v", bytes_written)],
+                false => vec![],
+            },
+            replica_stats: vec![stats::ReplicaIoStats {
+                entity_id: Some("v".to_string()),
+                stats: Some(io_stats(replica, bytes_written)),
+            }],
+            ..Default::default()
+        }
+    }
+
+    /// Cache the given nodes, each with its replica and whether it has the nexus.
+    async fn store_nodes(
+        cache: &SharedCache,
+        config: &ExporterConfig,
+        nodes: &[(&str, &str, bool)],
+        bytes_written: u64,
+    ) {
+        for (node, replica, nexus) in nodes {
+            let client = fake_node(replica, *nexus, bytes_written).serve();
+            store_node_data(&client, cache, node, config).await;
+        }
+    }
+
+    /// Get the label values along with the value of each metric of the family.
+    fn series(metric_families: &[MetricFamily], name: &str) -> Vec<(Vec<String>, f64)> {
+        metric_families
+            .iter()
+            .filter(|metric_family| metric_family.get_name() == name)
+            .flat_map(|metric_family| metric_family.get_metric())
+            .map(|metric| {
+                let labels = metric
+                    .get_label()
+                    .iter()
+                    .map(|label| label.get_value().to_string())
+                    .collect();
+                let value = match metric.has_counter() {
+                    true => metric.get_counter().get_value(),
+                    false => metric.get_gauge().get_value(),
+                };
+                (labels, value)
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_volume_rollup_across_nodes() {
+        let args = Cli::parse_from(["metrics-exporter"]);
+        let config = ExporterConfig::try_from(&args).unwrap();
+        let labels = vec!["n1".to_string(), "pvc-v".to_string()];
+
+        // In cluster mode, the replicas of the volume on both nodes are rolled up together, on
+        // the node of the nexus.
+        let cache = Cache::new_shared();
+        let nodes = [("n1", "replica-1", true), ("n2", "replica-2", false)];
+        store_nodes(&cache, &config, &nodes, 1000).await;
+        store_nodes(&cache, &config, &nodes, 3000).await;
+        let metric_families = VolumeRollupCollector::new(cache).collect();
+        assert_eq!(
+            series(&metric_families, "volume_replica_count"),
+            vec![(labels.clone(), 2.0)]
+        );
+        assert_eq!(
+            series(&metric_families, "volume_replica_writing_count"),
+            vec![(labels.clone(), 2.0)]
+        );
+        assert_eq!(
+            series(&metric_families, "volume_replica_bytes_written_total"),
+            vec![(labels.clone(), 6000.0)]
+        );
+        assert_eq!(
+            series(&metric_families, "volume_replica_write_ratio"),
+            vec![(labels, 2.0)]
+        );
+
+        // In per-node mode, without the nexus on the node, there is no ratio.
+        let cache = Cache::new_shared();
+        let nodes = [("n2", "replica-2", false)];
+        store_nodes(&cache, &config, &nodes, 1000).await;
+        store_nodes(&cache, &config, &nodes, 3000).await;
+        let metric_families = VolumeRollupCollector::new(cache).collect();
+        assert_eq!(
+            series(&metric_families, "volume_replica_count"),
+            vec![(vec!["n2".to_string(), "pvc-v".to_string()], 1.0)]
+        );
+        assert!(series(&metric_families, "volume_replica_write_ratio").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_volume_replica_counters_with_replica_gone() {
+        let args = Cli::parse_from(["metrics-exporter"]);
+        let config = ExporterConfig::try_from(&args).unwrap();
+        let labels = vec!["n1".to_string(), "pvc-v".to_string()];
+
+        let cache = Cache::new_shared();
+        let nodes = [("n1", "replica-1", true), ("n2", "replica-2", false)];
+        store_nodes(&cache, &config, &nodes, 1000).await;
+        store_nodes(&cache, &config, &nodes, 3000).await;
+        let metric_families = VolumeRollupCollector::new(cache.clone()).collect();
+        assert_eq!(
+            series(&metric_families, "volume_replica_bytes_written_total"),
+            vec![(labels.clone(), 6000.0)]
+        );
+
+        // The replica of the second node is gone, the bytes written to it are still counted.
+        let client = FakeIoEngine::default().serve();
+        store_node_data(&client, &cache, "n2", &config).await;
+        store_nodes(&cache, &config, &[("n1", "replica-1", true)], 4000).await;
+        let metric_families = VolumeRollupCollector::new(cache.clone()).collect();
+        assert_eq!(
+            series(&metric_families, "volume_replica_count"),
+            vec![(labels.clone(), 1.0)]
+        );
+        assert_eq!(
+            series(&metric_families, "volume_replica_bytes_written_total"),
+            vec![(labels, 7000.0)]
+        );
+
+        // Once the volume has no replica left, its counters are dropped.
+        store_node_data(&client, &cache, "n1", &config).await;
+        assert!(cache
+            .lock()
+            .unwrap()
+            .volume_replica_counters()
+            .get("v")
+            .is_none());
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{error::ExporterError, Cli};
+use serde::Deserialize;
+use std::{collections::HashMap, path::Path, str::FromStr, time::Duration};
+
+/// Families of collectors which can be enabled or disabled.
+#[derive(
+    Deserialize, clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Hash, strum_macros::AsRefStr,
+)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub(crate) enum CollectorFamily {
+    /// Pool capacity, status and commitment.
+    Pool,
+    /// Pool io stats.
+    PoolIostat,
+    /// Block devices backing the pools.
+    Disk,
+    /// Nexus and child status.
+    Nexus,
+    /// Nexus io stats.
+    NexusIostat,
+    /// Replica io stats.
+    ReplicaIostat,
+    /// Volume rollup of the replica io stats.
+    Volume,
+    /// Nexus child rebuilds.
+    Rebuild,
+    /// Health of the exporter itself.
+    Exporter,
+    /// Totals of all the io-engine nodes, only exported in cluster mode.
+    Cluster,
+}
+
+/// A label to be dropped from the metrics of a collector family, given as `<family>:<label>`.
+#[derive(Debug, Clone)]
+pub(crate) struct DropLabel {
+    family: CollectorFamily,
+    label: String,
+}
+
+impl FromStr for DropLabel {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let Some((family, label)) = value.split_once(':') else {
+            return Err(format!("'{value}' is not of the form <family>:<label>"));
+        };
+        let family = <CollectorFamily as clap::ValueEnum>::from_str(family, false)?;
+        if label.is_empty() {
+            return Err(format!("'{value}' has no label"));
+        }
+        Ok(Self {
+            family,
+            label: label.to_string(),
+        })
+    }
+}
+
+/// Metrics configuration which can be provided through a config file, in addition to the
+/// command line arguments.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+struct MetricsConfigFile {
+    /// Collector families not to be exported.
+    disabled_collectors: Vec<CollectorFamily>,
+    /// Labels to be dropped from the metrics of each collector family.
+    drop_labels: HashMap<CollectorFamily, Vec<String>>,
+    /// Maximum number of series exported per scrape.
+    max_series: Option<usize>,
+}
+
+impl MetricsConfigFile {
+    /// Read the metrics configuration from the YAML file at the given path.
+    fn read(path: &Path) -> Result<Self, ExporterError> {
+        let file = std::fs::File::open(path).map_err(|error| {
+            ExporterError::ConfigError(format!("Failed to open {}: {error}", path.display()))
+        })?;
+        serde_yaml::from_reader(file).map_err(|error| {
+            ExporterError::ConfigError(format!("Failed to parse {}: {error}", path.display()))
+        })
+    }
+}
+
+/// Configuration of the exporter, derived from the command line arguments and config file.
+#[derive(Debug, Clone)]
+pub(crate) struct ExporterConfig {
+    /// Also export the cumulative io stats as gauges with their old names.
+    legacy_iostat_gauges: bool,
+    /// Window of pool capacity samples used to forecast when pools get full.
+    capacity_forecast_window: Duration,
+    /// Collector families not to be exported.
+    disabled_collectors: Vec<CollectorFamily>,
+    /// Labels to be dropped from the metrics of each collector family.
+    drop_labels: HashMap<CollectorFamily, Vec<String>>,
+    /// Maximum number of series exported per scrape.
+    max_series: Option<usize>,
+    /// Whether the exporter scrapes all the io-engine nodes of the cluster.
+    cluster_mode: bool,
+    /// Whether the debug endpoints are served, which requires the gRPC exchanges to be recorded.
+    debug_endpoints: bool,
+    /// Number of retries of a gRPC request which could not reach io-engine.
+    grpc_retries: u32,
+    /// Backoff before the first retry of a gRPC request.
+    grpc_retry_backoff: Duration,
+    /// Number of consecutive gRPC requests which could not reach io-engine to open the circuit.
+    grpc_circuit_failure_threshold: u32,
+    /// Duration for which no gRPC requests are sent once the circuit is open.
+    grpc_circuit_cooldown: Duration,
+}
+
+impl ExporterConfig {
+    /// Whether the cumulative io stats are also exported as gauges with their old names.
+    pub(crate) fn legacy_iostat_gauges(&self) -> bool {
+        self.legacy_iostat_gauges
+    }
+
+    /// Window of pool capacity samples used to forecast when pools get full.
+    pub(crate) fn capacity_forecast_window(&self) -> Duration {
+        self.capacity_forecast_window
+    }
+
+    /// Whether the metrics of the collector family are exported.
+    pub(crate) fn collector_enabled(&self, family: CollectorFamily) -> bool {
+        if family == CollectorFamily::Cluster && !self.cluster_mode {
+            return false;
+        }
+        !self.disabled_collectors.contains(&family)
+    }
+
+    /// Labels to be dropped from the metrics of the collector family.
+    pub(crate) fn drop_labels(&self, family: CollectorFamily) -> &[String] {
+        self.drop_labels
+            .get(&family)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Maximum number of series exported per scrape, if limited.
+    pub(crate) fn max_series(&self) -> Option<usize> {
+        self.max_series
+    }
+
+    /// Whether the debug endpoints are served.
+    pub(crate) fn debug_endpoints(&self) -> bool {
+        self.debug_endpoints
+    }
+
+    /// Number of retries of a gRPC request which could not reach io-engine.
+    pub(crate) fn grpc_retries(&self) -> u32 {
+        self.grpc_retries
+    }
+
+    /// Backoff before the first retry of a gRPC request, doubled on each retry.
+    pub(crate) fn grpc_retry_backoff(&self) -> Duration {
+        self.grpc_retry_backoff
+    }
+
+    /// Number of consecutive gRPC requests which could not reach io-engine to open the circuit.
+    pub(crate) fn grpc_circuit_failure_threshold(&self) -> u32 {
+        self.grpc_circuit_failure_threshold
+    }
+
+    /// Duration for which no gRPC requests are sent once the circuit is open.
+    pub(crate) fn grpc_circuit_cooldown(&self) -> Duration {
+        self.grpc_circuit_cooldown
+    }
+}
+
+impl TryFrom<&Cli> for ExporterConfig {
+    type Error = ExporterError;
+
+    fn try_from(args: &Cli) -> Result<Self, Self::Error> {
+        let file = match &args.config_file {
+            Some(path) => MetricsConfigFile::read(path)?,
+            None => MetricsConfigFile::default(),
+        };
+
+        let mut disabled_collectors = file.disabled_collectors;
+        disabled_collectors.extend(args.disable_collectors.iter().copied());
+        let mut drop_labels = file.drop_labels;
+        for drop_label in &args.drop_labels {
+            drop_labels
+                .entry(drop_label.family)
+                .or_default()
+                .push(drop_label.label.clone());
+        }
+
+        Ok(Self {
+            legacy_iostat_gauges: args.legacy_iostat_gauges,
+            capacity_forecast_window: args.capacity_forecast_window.into(),
+            disabled_collectors,
+            drop_labels,
+            max_series: args.max_series.or(file.max_series),
+            cluster_mode: args.cluster_mode,
+            debug_endpoints: args.debug_endpoints,
+            grpc_retries: args.grpc_retries,
+            grpc_retry_backoff: args.grpc_retry_backoff.into(),
+            grpc_circuit_failure_threshold: args.grpc_circuit_failure_threshold,
+            grpc_circuit_cooldown: args.grpc_circuit_cooldown.into(),
+        })
+    }
+}
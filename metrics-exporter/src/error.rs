@@ -8,4 +8,9 @@ pub enum ExporterError {
     GrpcClientError(String),
     HttpServerError(String),
     HttpBindError(String),
+    ConfigError(String),
+    TlsConfigError(String),
+    AuthConfigError(String),
+    PushError(String),
+    DiscoveryError(String),
 }
@@ -0,0 +1,296 @@
+//! The io-engine metrics exporter. The configuration, the cache, the gRPC client it is populated
+//! with and the collectors reading it are injected, so that they can be tested against a fake
+//! io-engine.
+
+use crate::{
+    cache::{store_resource_data, Cache},
+    client::grpc_client::init_client,
+    cluster::{store_cluster_data, Discovery},
+    config::{CollectorFamily, DropLabel, ExporterConfig},
+    error::ExporterError,
+    push::{otlp::OtlpProtocol, push_metrics, PushTarget, Pusher},
+    serve::{
+        auth::{Authenticator, BearerAuth},
+        debug_route, metric_route, tls,
+    },
+};
+use actix_web::{middleware, HttpServer};
+use clap::Parser;
+use std::{
+    env,
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    sync::Arc,
+};
+use tracing::error;
+use utils::tracing_telemetry::{FmtLayer, FmtStyle};
+
+/// Cache module for exporter.
+pub(crate) mod cache;
+/// Grpc client module.
+pub(crate) mod client;
+/// Cluster mode module, discovering all the io-engine nodes.
+pub(crate) mod cluster;
+/// Collector module.
+pub(crate) mod collector;
+/// Exporter configuration module.
+pub(crate) mod config;
+/// Error module.
+pub mod error;
+/// Prometheus metrics push module.
+pub(crate) mod push;
+/// Prometheus metrics handler module.
+pub(crate) mod serve;
+
+/// Get pod ip from env.
+fn get_pod_ip() -> Result<IpAddr, ExporterError> {
+    let ip = env::var("MY_POD_IP")
+        .map_err(|_| ExporterError::PodIPError("Unable to get pod ip".to_string()))?;
+    ip.parse::<IpAddr>()
+        .map_err(|_| ExporterError::PodIPError("Invalid pod ip".to_string()))
+}
+
+/// Get node name from env.
+fn get_node_name() -> Result<String, ExporterError> {
+    env::var("MY_NODE_NAME")
+        .map_err(|_| ExporterError::GetNodeError("Unable to get node name".to_string()))
+}
+
+#[derive(Parser, Debug)]
+#[clap(name = utils::package_description!(), version = utils::version_info_str!())]
+pub struct Cli {
+    /// TCP address where prometheus endpoint will listen to
+    #[clap(long, short, default_value = "[::]:9502")]
+    metrics_endpoint: SocketAddr,
+
+    /// Interval at which the cache is refreshed with the metrics data from io-engine.
+    #[clap(long, short, default_value = "30s")]
+    polling_time: humantime::Duration,
+
+    /// Also export the cumulative io stats as gauges with their names prior to the `_total`
+    /// counters. Deprecated, will be removed in the next release.
+    #[clap(long, default_value_t = true, action = clap::ArgAction::Set)]
+    legacy_iostat_gauges: bool,
+
+    /// Window of pool capacity samples used to forecast when pools get full.
+    #[clap(long, default_value = "24h")]
+    capacity_forecast_window: humantime::Duration,
+
+    /// Collector families whose metrics are not exported.
+    #[clap(long, value_delimiter = ',')]
+    disable_collectors: Vec<CollectorFamily>,
+
+    /// Labels to be dropped from the metrics of a collector family, as `<family>:<label>`, eg:
+    /// `replica_iostat:name`. The series which only differ by a dropped label are aggregated:
+    /// counters and histograms are summed, and gauges are averaged.
+    #[clap(long, value_delimiter = ',')]
+    drop_labels: Vec<DropLabel>,
+
+    /// Maximum number of series exported per scrape, the series beyond it are dropped with a
+    /// warning.
+    #[clap(long)]
+    max_series: Option<usize>,
+
+    /// YAML file with the `disabledCollectors`, `dropLabels` and `maxSeries` configuration, which
+    /// is merged with the command line arguments.
+    #[clap(long)]
+    config_file: Option<PathBuf>,
+
+    /// Discover all the io-engine pods through the Kubernetes API and export the metrics of all
+    /// their nodes along with the cluster totals, instead of the metrics of the local io-engine.
+    #[clap(long)]
+    cluster_mode: bool,
+
+    /// Namespace of the io-engine pods in cluster mode, the namespace of the exporter by default.
+    #[clap(long, requires = "cluster_mode")]
+    io_engine_namespace: Option<String>,
+
+    /// Label selector of the io-engine pods in cluster mode.
+    #[clap(long, default_value = "app=io-engine")]
+    io_engine_selector: String,
+
+    /// Number of retries of a gRPC request which could not reach io-engine.
+    #[clap(long, default_value_t = 2)]
+    grpc_retries: u32,
+
+    /// Backoff before the first retry of a gRPC request, doubled on each retry.
+    #[clap(long, default_value = "500ms")]
+    grpc_retry_backoff: humantime::Duration,
+
+    /// Number of consecutive gRPC requests which could not reach io-engine after which no
+    /// requests are sent until the cooldown elapses.
+    #[clap(long, default_value_t = 5)]
+    grpc_circuit_failure_threshold: u32,
+
+    /// Duration for which no gRPC requests are sent once io-engine is unreachable.
+    #[clap(long, default_value = "30s")]
+    grpc_circuit_cooldown: humantime::Duration,
+
+    /// Serve the cached data of each node on `/debug/cache`, and the last gRPC response and error
+    /// of each method on `/debug/grpc`, as JSON. These are protected by the same authentication
+    /// as the metrics endpoint.
+    #[clap(long)]
+    debug_endpoints: bool,
+
+    /// PEM file with the TLS certificate served on the metrics endpoint. The certificate and key
+    /// are reloaded whenever they are modified.
+    #[clap(long, requires = "tls_key_file")]
+    tls_cert_file: Option<PathBuf>,
+
+    /// PEM file with the private key of the TLS certificate.
+    #[clap(long, requires = "tls_cert_file")]
+    tls_key_file: Option<PathBuf>,
+
+    /// PEM file with the CA certificates the clients certificates must be signed by, which
+    /// enables mutual TLS.
+    #[clap(long, requires = "tls_cert_file")]
+    tls_client_ca_file: Option<PathBuf>,
+
+    /// File with the bearer tokens allowed to scrape the metrics endpoint, one per line. The
+    /// tokens are reloaded whenever the file is modified.
+    #[clap(long)]
+    auth_token_file: Option<PathBuf>,
+
+    /// Allow the bearer tokens authenticated by the Kubernetes TokenReview API to scrape the
+    /// metrics endpoint, if their user is allowed to get the requested path by the Kubernetes
+    /// SubjectAccessReview API.
+    #[clap(long)]
+    auth_token_review: bool,
+
+    /// Prometheus Pushgateway to push the metrics to on the push interval, eg:
+    /// `http://pushgateway:9091`.
+    #[clap(long)]
+    pushgateway_url: Option<reqwest::Url>,
+
+    /// Prometheus remote-write endpoint to push the metrics to on the push interval, eg:
+    /// `http://prometheus:9090/api/v1/write`.
+    #[clap(long)]
+    remote_write_url: Option<reqwest::Url>,
+
+    /// OpenTelemetry OTLP endpoint to export the metrics to on the push interval, eg:
+    /// `http://otel-collector:4317` for gRPC.
+    #[clap(long)]
+    otlp_endpoint: Option<reqwest::Url>,
+
+    /// Transport protocol of the OTLP endpoint.
+    #[clap(long, value_enum, default_value_t = OtlpProtocol::Grpc)]
+    otlp_protocol: OtlpProtocol,
+
+    /// Interval at which the metrics are pushed.
+    #[clap(long, default_value = "30s")]
+    push_interval: humantime::Duration,
+
+    /// Job label of the pushed metrics, their instance label being the node name.
+    #[clap(long, default_value = "mayastor-io-engine")]
+    push_job: String,
+
+    /// File with the bearer token sent along with the pushed metrics.
+    #[clap(long)]
+    push_bearer_token_file: Option<PathBuf>,
+
+    /// Formatting style to be used while logging.
+    #[clap(default_value = FmtStyle::Pretty.as_ref(), short, long)]
+    fmt_style: FmtStyle,
+
+    /// Use ANSI colors for the logs.
+    #[clap(long, default_value_t = true, action = clap::ArgAction::Set)]
+    ansi_colors: bool,
+}
+
+impl Cli {
+    /// Parse the command line arguments.
+    pub fn args() -> Self {
+        Cli::parse()
+    }
+}
+
+/// Run the exporter with the command line arguments until its metrics endpoint is shut down.
+pub async fn run(args: Cli) -> Result<(), ExporterError> {
+    utils::tracing_telemetry::TracingTelemetry::builder()
+        .with_writer(FmtLayer::Stdout)
+        .with_style(args.fmt_style)
+        .with_colours(args.ansi_colors)
+        .init("metrics-exporter-io_engine");
+
+    let config = Arc::new(ExporterConfig::try_from(&args)?);
+    let cache = Cache::new_shared();
+    // Refresh the cache in the background, scrapes only read from the cache.
+    let polling_time = args.polling_time.into();
+    if args.cluster_mode {
+        let discovery = Discovery::new(args.io_engine_namespace, args.io_engine_selector).await?;
+        let cache = cache.clone();
+        let config = config.clone();
+        tokio::spawn(async move {
+            store_cluster_data(discovery, cache, config, polling_time).await;
+            error!("Cache refresh task exited");
+        });
+    } else {
+        let client = init_client(get_pod_ip()?, &config)?;
+        let node_name = get_node_name()?;
+        let cache = cache.clone();
+        let config = config.clone();
+        tokio::spawn(async move {
+            store_resource_data(&client, &cache, &node_name, &config, polling_time).await;
+            error!("Cache refresh task exited");
+        });
+    }
+    let push_targets = args
+        .pushgateway_url
+        .map(PushTarget::Pushgateway)
+        .into_iter()
+        .chain(args.remote_write_url.map(PushTarget::RemoteWrite))
+        .chain(
+            args.otlp_endpoint
+                .map(|url| PushTarget::Otlp(url, args.otlp_protocol)),
+        )
+        .collect::<Vec<_>>();
+    if !push_targets.is_empty() {
+        let pusher = Pusher::new(
+            push_targets,
+            args.push_job,
+            get_node_name()?,
+            args.push_bearer_token_file,
+        )?;
+        let push_interval = args.push_interval.into();
+        let cache = cache.clone();
+        let config = config.clone();
+        tokio::spawn(async move {
+            push_metrics(pusher, cache, config, push_interval).await;
+        });
+    }
+    let authenticator = Authenticator::new(args.auth_token_file, args.auth_token_review)
+        .await?
+        .map(Arc::new);
+    let auth = BearerAuth::new(authenticator);
+    let debug_endpoints = args.debug_endpoints;
+    let app = move || {
+        let auth = auth.clone();
+        let cache = cache.clone();
+        let config = config.clone();
+        actix_web::App::new()
+            .wrap(middleware::Logger::default())
+            .configure(|cfg| {
+                metric_route(cfg, auth.clone(), cache, config);
+                if debug_endpoints {
+                    debug_route(cfg, auth);
+                }
+            })
+    };
+    let server = HttpServer::new(app);
+    let server = match (args.tls_cert_file, args.tls_key_file) {
+        (Some(cert_file), Some(key_file)) => {
+            let config = tls::server_config(cert_file, key_file, args.tls_client_ca_file)?;
+            server.bind_rustls_021(args.metrics_endpoint, config)
+        }
+        _ => server.bind(args.metrics_endpoint),
+    };
+    server
+        .map_err(|_| {
+            ExporterError::HttpBindError("Failed to bind endpoint to http server".to_string())
+        })?
+        .workers(1)
+        .run()
+        .await
+        .map_err(|_| ExporterError::HttpServerError("Failed to start http Service".to_string()))?;
+    Ok(())
+}
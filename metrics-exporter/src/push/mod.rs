@@ -0,0 +1,388 @@
+/// OpenTelemetry OTLP encoding module.
+pub(crate) mod otlp;
+/// Prometheus remote-write encoding module.
+mod remote_write;
+
+use crate::{
+    cache::SharedCache, collector::registry::gather, config::ExporterConfig, error::ExporterError,
+    serve::encoder::ExpositionFormat,
+};
+use otlp::OtlpProtocol;
+use prometheus::proto::MetricFamily;
+use reqwest::{header, Client, RequestBuilder, Url};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use tokio::time::sleep;
+use tonic::{
+    metadata::MetadataValue,
+    transport::{Channel, Endpoint},
+};
+use tracing::{debug, error};
+
+/// Endpoint the metrics are pushed to.
+#[derive(Debug, Clone)]
+pub(crate) enum PushTarget {
+    /// Prometheus Pushgateway, the metrics replace the ones of the same job and instance.
+    Pushgateway(Url),
+    /// Prometheus remote-write endpoint.
+    RemoteWrite(Url),
+    /// OpenTelemetry OTLP metrics endpoint, with its transport protocol.
+    Otlp(Url, OtlpProtocol),
+}
+
+/// Pushes the metrics gathered from the collectors to the push targets.
+pub(crate) struct Pusher {
+    client: Client,
+    targets: Vec<PushTarget>,
+    job: String,
+    instance: String,
+    bearer_token_file: Option<PathBuf>,
+    /// Channels to the OTLP gRPC endpoints.
+    otlp_channels: HashMap<Url, Channel>,
+    /// Start time of the cumulative OTLP sums.
+    start_time: SystemTime,
+}
+
+impl Pusher {
+    /// Create a new pusher, the metrics are labelled with the job and instance.
+    pub(crate) fn new(
+        targets: Vec<PushTarget>,
+        job: String,
+        instance: String,
+        bearer_token_file: Option<PathBuf>,
+    ) -> Result<Self, ExporterError> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|error| ExporterError::PushError(error.to_string()))?;
+        let mut otlp_channels = HashMap::new();
+        for target in &targets {
+            if let PushTarget::Otlp(url, OtlpProtocol::Grpc) = target {
+                let endpoint = Endpoint::from_shared(url.to_string())
+                    .map_err(|error| {
+                        ExporterError::PushError(format!("Invalid OTLP url {url}: {error}"))
+                    })?
+                    .timeout(Duration::from_secs(10));
+                otlp_channels.insert(url.clone(), endpoint.connect_lazy());
+            }
+        }
+        Ok(Self {
+            client,
+            targets,
+            job,
+            instance,
+            bearer_token_file,
+            otlp_channels,
+            start_time: SystemTime::now(),
+        })
+    }
+
+    /// Read the bearer token from the file, on each push so that a rotated token is picked up.
+    fn bearer_token(&self) -> Result<Option<String>, ExporterError> {
+        self.bearer_token_file
+            .as_ref()
+            .map(|path| {
+                std::fs::read_to_string(path)
+                    .map(|token| token.trim().to_string())
+                    .map_err(|error| {
+                        ExporterError::PushError(format!(
+                            "Failed to read {}: {error}",
+                            path.display()
+                        ))
+                    })
+            })
+            .transpose()
+    }
+
+    /// Add the bearer token to the request.
+    fn authorize(&self, request: RequestBuilder) -> Result<RequestBuilder, ExporterError> {
+        match self.bearer_token()? {
+            Some(token) => Ok(request.bearer_auth(token)),
+            None => Ok(request),
+        }
+    }
+
+    /// Push the metric families to the Pushgateway at the url, under the grouping key of the job
+    /// and instance.
+    async fn push_gateway(
+        &self,
+        url: &Url,
+        metric_families: &[MetricFamily],
+    ) -> Result<(), ExporterError> {
+        let mut grouping_url = url.clone();
+        grouping_url
+            .path_segments_mut()
+            .map_err(|_| ExporterError::PushError(format!("Invalid Pushgateway url {url}")))?
+            .pop_if_empty()
+            .extend(["metrics", "job", &self.job, "instance", &self.instance]);
+        let format = ExpositionFormat::Text;
+        let body = format
+            .encode(metric_families)
+            .map_err(|error| ExporterError::PushError(error.to_string()))?;
+        let request = self
+            .client
+            .put(grouping_url)
+            .header(header::CONTENT_TYPE, format.content_type())
+            .body(body);
+        self.send(request).await
+    }
+
+    /// Push the metric families to the Prometheus remote-write endpoint at the url.
+    async fn remote_write(
+        &self,
+        url: &Url,
+        metric_families: &[MetricFamily],
+    ) -> Result<(), ExporterError> {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+        let common_labels = [
+            ("job".to_string(), self.job.clone()),
+            ("instance".to_string(), self.instance.clone()),
+        ];
+        let write_request = remote_write::write_request(metric_families, &common_labels, timestamp);
+        let body = remote_write::encode(&write_request)
+            .map_err(|error| ExporterError::PushError(error.to_string()))?;
+        let request = self
+            .client
+            .post(url.clone())
+            .header(header::CONTENT_TYPE, remote_write::CONTENT_TYPE)
+            .header(header::CONTENT_ENCODING, remote_write::CONTENT_ENCODING)
+            .header("X-Prometheus-Remote-Write-Version", remote_write::VERSION)
+            .body(body);
+        self.send(request).await
+    }
+
+    /// Export the metric families to the OpenTelemetry OTLP endpoint at the url, with the job and
+    /// instance as the service name and instance id of the resources.
+    async fn otlp(
+        &self,
+        url: &Url,
+        protocol: OtlpProtocol,
+        metric_families: &[MetricFamily],
+    ) -> Result<(), ExporterError> {
+        let common_attributes = [
+            ("service.name", self.job.as_str()),
+            ("service.instance.id", self.instance.as_str()),
+        ];
+        let export_request = otlp::export_request(
+            metric_families,
+            &common_attributes,
+            self.start_time,
+            SystemTime::now(),
+        );
+        match protocol {
+            OtlpProtocol::Grpc => {
+                let Some(channel) = self.otlp_channels.get(url) else {
+                    return Err(ExporterError::PushError(format!(
+                        "No channel to the OTLP endpoint {url}"
+                    )));
+                };
+                let mut request = tonic::Request::new(export_request);
+                if let Some(token) = self.bearer_token()? {
+                    let value = MetadataValue::try_from(format!("Bearer {token}"))
+                        .map_err(|error| ExporterError::PushError(error.to_string()))?;
+                    request.metadata_mut().insert("authorization", value);
+                }
+                let response = otlp::export_grpc(channel.clone(), request)
+                    .await
+                    .map_err(|error| ExporterError::PushError(error.to_string()))?;
+                if let Some(partial_success) = response.partial_success {
+                    if partial_success.rejected_data_points > 0 {
+                        return Err(ExporterError::PushError(format!(
+                            "{} data points rejected: {}",
+                            partial_success.rejected_data_points, partial_success.error_message
+                        )));
+                    }
+                }
+                Ok(())
+            }
+            OtlpProtocol::HttpProtobuf => {
+                let request = self
+                    .client
+                    .post(url.clone())
+                    .header(header::CONTENT_TYPE, otlp::CONTENT_TYPE)
+                    .body(prost::Message::encode_to_vec(&export_request));
+                self.send(request).await
+            }
+        }
+    }
+
+    /// Send the push request, failing on a non-success response.
+    async fn send(&self, request: RequestBuilder) -> Result<(), ExporterError> {
+        let response = self
+            .authorize(request)?
+            .send()
+            .await
+            .map_err(|error| ExporterError::PushError(error.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ExporterError::PushError(format!(
+                "Push rejected with {status}: {body}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Push the metric families to all the push targets.
+    pub(crate) async fn push(&self, metric_families: &[MetricFamily]) {
+        for target in &self.targets {
+            let result = match target {
+                PushTarget::Pushgateway(url) => self.push_gateway(url, metric_families).await,
+                PushTarget::RemoteWrite(url) => self.remote_write(url, metric_families).await,
+                PushTarget::Otlp(url, protocol) => self.otlp(url, *protocol, metric_families).await,
+            };
+            match result {
+                Ok(()) => debug!(?target, "Pushed metrics"),
+                Err(error) => error!(?error, ?target, "Error while pushing metrics"),
+            }
+        }
+    }
+}
+
+/// Gathers the metrics from the collectors reading the cache and pushes them to the push targets
+/// periodically, sleeping for the push interval between pushes.
+pub(crate) async fn push_metrics(
+    pusher: Pusher,
+    cache: SharedCache,
+    config: Arc<ExporterConfig>,
+    interval: Duration,
+) {
+    loop {
+        sleep(interval).await;
+        let metric_families = gather(&cache, &config);
+        pusher.push(&metric_families).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+    use prometheus::{core::Collector, GaugeVec, Opts};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    /// Accept a single HTTP request on the listener and return its head and body.
+    async fn receive(listener: TcpListener) -> (String, Vec<u8>) {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0u8; 4096];
+        loop {
+            let read = stream.read(&mut buffer).await.unwrap();
+            request.extend_from_slice(&buffer[.. read]);
+            let Some(end) = request.windows(4).position(|window| window == b"\r\n\r\n") else {
+                continue;
+            };
+            let head = String::from_utf8_lossy(&request[.. end]).to_lowercase();
+            let length = head
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .map(|length| length.trim().parse::<usize>().unwrap())
+                .unwrap_or_default();
+            if request.len() >= end + 4 + length {
+                stream
+                    .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                    .await
+                    .unwrap();
+                return (head, request[end + 4 .. end + 4 + length].to_vec());
+            }
+        }
+    }
+
+    fn metric_families() -> Vec<MetricFamily> {
+        let gauge_vec = GaugeVec::new(Opts::new("used", "used"), &["name"]).unwrap();
+        gauge_vec.with_label_values(&["pool-1"]).set(3.0);
+        gauge_vec.collect()
+    }
+
+    #[tokio::test]
+    async fn test_push_remote_write() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!(
+            "http://{}/api/v1/write",
+            listener.local_addr().unwrap()
+        ))
+        .unwrap();
+        let receiver = tokio::spawn(receive(listener));
+        let pusher = Pusher::new(
+            vec![PushTarget::RemoteWrite(url.clone())],
+            "mayastor".to_string(),
+            "node-1".to_string(),
+            None,
+        )
+        .unwrap();
+        pusher.remote_write(&url, &metric_families()).await.unwrap();
+
+        let (head, body) = receiver.await.unwrap();
+        assert!(head.starts_with("post /api/v1/write "));
+        assert!(head.contains("content-encoding: snappy"));
+        let body = snap::raw::Decoder::new().decompress_vec(&body).unwrap();
+        let request =
+            <remote_write::WriteRequest as prost::Message>::decode(body.as_slice()).unwrap();
+        assert_eq!(request.timeseries.len(), 1);
+        assert_eq!(request.timeseries[0].samples[0].value, 3.0);
+    }
+
+    #[tokio::test]
+    async fn test_push_otlp_http() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!(
+            "http://{}/v1/metrics",
+            listener.local_addr().unwrap()
+        ))
+        .unwrap();
+        let receiver = tokio::spawn(receive(listener));
+        let pusher = Pusher::new(
+            vec![PushTarget::Otlp(url.clone(), OtlpProtocol::HttpProtobuf)],
+            "mayastor".to_string(),
+            "node-1".to_string(),
+            None,
+        )
+        .unwrap();
+        pusher
+            .otlp(&url, OtlpProtocol::HttpProtobuf, &metric_families())
+            .await
+            .unwrap();
+
+        let (head, body) = receiver.await.unwrap();
+        assert!(head.starts_with("post /v1/metrics "));
+        assert!(head.contains("content-type: application/x-protobuf"));
+        let request =
+            <ExportMetricsServiceRequest as prost::Message>::decode(body.as_slice()).unwrap();
+        assert_eq!(request.resource_metrics.len(), 1);
+        assert_eq!(
+            request.resource_metrics[0].scope_metrics[0].metrics[0].name,
+            "used"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_push_gateway() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        let receiver = tokio::spawn(receive(listener));
+        let pusher = Pusher::new(
+            vec![PushTarget::Pushgateway(url.clone())],
+            "mayastor".to_string(),
+            "node-1".to_string(),
+            None,
+        )
+        .unwrap();
+        pusher.push_gateway(&url, &metric_families()).await.unwrap();
+
+        let (head, body) = receiver.await.unwrap();
+        assert!(head.starts_with("put /metrics/job/mayastor/instance/node-1 "));
+        assert!(String::from_utf8(body)
+            .unwrap()
+            .contains("used{name=\"pool-1\"} 3"));
+    }
+}
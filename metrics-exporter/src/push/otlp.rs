@@ -0,0 +1,376 @@
+use opentelemetry_proto::tonic::{
+    collector::metrics::v1::{ExportMetricsServiceRequest, ExportMetricsServiceResponse},
+    common::v1::{any_value, AnyValue, InstrumentationScope, KeyValue},
+    metrics::v1::{
+        metric::Data, number_data_point, summary_data_point::ValueAtQuantile,
+        AggregationTemporality, Gauge, Histogram, HistogramDataPoint, Metric as OtlpMetric,
+        NumberDataPoint, ResourceMetrics, ScopeMetrics, Sum, Summary, SummaryDataPoint,
+    },
+    resource::v1::Resource,
+};
+use prometheus::proto::{Metric, MetricFamily, MetricType};
+use std::{
+    collections::BTreeMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tonic::{
+    codec::ProstCodec, codegen::http::uri::PathAndQuery, transport::Channel, Request, Status,
+};
+
+/// Content type of the OTLP/HTTP protobuf requests.
+pub(crate) const CONTENT_TYPE: &str = "application/x-protobuf";
+/// Path of the OTLP metrics export gRPC method.
+const EXPORT_PATH: &str = "/opentelemetry.proto.collector.metrics.v1.MetricsService/Export";
+/// Name of the instrumentation scope of the exported metrics.
+const SCOPE_NAME: &str = "mayastor-io-engine-exporter";
+
+/// Resource attribute of the node.
+const NODE_ATTRIBUTE: &str = "k8s.node.name";
+/// Resource attribute of the pool.
+const POOL_ATTRIBUTE: &str = "mayastor.pool.name";
+/// Resource attribute of the volume.
+const VOLUME_ATTRIBUTE: &str = "mayastor.volume.name";
+
+/// Transport protocol of the OTLP exporter.
+#[derive(clap::ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[value(rename_all = "kebab-case")]
+pub(crate) enum OtlpProtocol {
+    /// OTLP over gRPC, eg: to `http://otel-collector:4317`.
+    #[default]
+    Grpc,
+    /// OTLP over HTTP with protobuf payloads, eg: to `http://otel-collector:4318/v1/metrics`.
+    HttpProtobuf,
+}
+
+/// Build an attribute with a string value.
+fn attribute(key: &str, value: &str) -> KeyValue {
+    KeyValue {
+        key: key.to_string(),
+        value: Some(AnyValue {
+            value: Some(any_value::Value::StringValue(value.to_string())),
+        }),
+    }
+}
+
+/// Convert the time to nanoseconds since the UNIX epoch.
+fn unix_nano(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64
+}
+
+/// Split the labels of the metric into the attributes of the resource it belongs to, ie: its node,
+/// pool and volume, and the attributes of its data point.
+fn split_attributes(family_name: &str, metric: &Metric) -> (Vec<(String, String)>, Vec<KeyValue>) {
+    let mut resource = Vec::new();
+    let mut attributes = Vec::new();
+    for label in metric.get_label() {
+        let key = match label.get_name() {
+            "node" => NODE_ATTRIBUTE,
+            "pv_name" => VOLUME_ATTRIBUTE,
+            // The name label of the diskpool metrics is the pool, it is the replica for the
+            // replica metrics.
+            "name" if family_name.starts_with("diskpool_") => POOL_ATTRIBUTE,
+            name => {
+                attributes.push(attribute(name, label.get_value()));
+                continue;
+            }
+        };
+        resource.push((key.to_string(), label.get_value().to_string()));
+    }
+    resource.sort();
+    (resource, attributes)
+}
+
+/// Convert the data points of the metric family belonging to the same resource to an OTLP metric.
+fn otlp_metric(
+    metric_family: &MetricFamily,
+    metrics: Vec<(Vec<KeyValue>, &Metric)>,
+    start_time: u64,
+    time: u64,
+) -> OtlpMetric {
+    let number_data_points = |value: fn(&Metric) -> f64| {
+        metrics
+            .iter()
+            .map(|(attributes, metric)| NumberDataPoint {
+                attributes: attributes.clone(),
+                start_time_unix_nano: start_time,
+                time_unix_nano: time,
+                value: Some(number_data_point::Value::AsDouble(value(metric))),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>()
+    };
+    let data = match metric_family.get_field_type() {
+        MetricType::COUNTER => Data::Sum(Sum {
+            data_points: number_data_points(|metric| metric.get_counter().get_value()),
+            aggregation_temporality: AggregationTemporality::Cumulative as i32,
+            is_monotonic: true,
+        }),
+        MetricType::GAUGE => Data::Gauge(Gauge {
+            data_points: number_data_points(|metric| metric.get_gauge().get_value()),
+        }),
+        MetricType::UNTYPED => Data::Gauge(Gauge {
+            data_points: number_data_points(|metric| metric.get_untyped().get_value()),
+        }),
+        MetricType::HISTOGRAM => Data::Histogram(Histogram {
+            data_points: metrics
+                .iter()
+                .map(|(attributes, metric)| {
+                    let histogram = metric.get_histogram();
+                    let buckets = histogram
+                        .get_bucket()
+                        .iter()
+                        .filter(|bucket| bucket.get_upper_bound().is_finite())
+                        .collect::<Vec<_>>();
+                    // OTLP bucket counts are not cumulative, and include the +Inf bucket.
+                    let mut previous = 0;
+                    let mut bucket_counts = buckets
+                        .iter()
+                        .map(|bucket| {
+                            let count = bucket.get_cumulative_count().saturating_sub(previous);
+                            previous = bucket.get_cumulative_count();
+                            count
+                        })
+                        .collect::<Vec<_>>();
+                    bucket_counts.push(histogram.get_sample_count().saturating_sub(previous));
+                    HistogramDataPoint {
+                        attributes: attributes.clone(),
+                        start_time_unix_nano: start_time,
+                        time_unix_nano: time,
+                        count: histogram.get_sample_count(),
+                        sum: Some(histogram.get_sample_sum()),
+                        bucket_counts,
+                        explicit_bounds: buckets
+                            .iter()
+                            .map(|bucket| bucket.get_upper_bound())
+                            .collect(),
+                        ..Default::default()
+                    }
+                })
+                .collect(),
+            aggregation_temporality: AggregationTemporality::Cumulative as i32,
+        }),
+        MetricType::SUMMARY => Data::Summary(Summary {
+            data_points: metrics
+                .iter()
+                .map(|(attributes, metric)| {
+                    let summary = metric.get_summary();
+                    SummaryDataPoint {
+                        attributes: attributes.clone(),
+                        start_time_unix_nano: start_time,
+                        time_unix_nano: time,
+                        count: summary.get_sample_count(),
+                        sum: summary.get_sample_sum(),
+                        quantile_values: summary
+                            .get_quantile()
+                            .iter()
+                            .map(|quantile| ValueAtQuantile {
+                                quantile: quantile.get_quantile(),
+                                value: quantile.get_value(),
+                            })
+                            .collect(),
+                        ..Default::default()
+                    }
+                })
+                .collect(),
+        }),
+    };
+    OtlpMetric {
+        name: metric_family.get_name().to_string(),
+        description: metric_family.get_help().to_string(),
+        data: Some(data),
+        ..Default::default()
+    }
+}
+
+/// Convert the metric families to an OTLP export request. The series are grouped into resources
+/// by their node, pool and volume, with the common attributes added to all the resources.
+/// Counters are cumulative sums starting at the start time.
+pub(crate) fn export_request(
+    metric_families: &[MetricFamily],
+    common_attributes: &[(&str, &str)],
+    start_time: SystemTime,
+    time: SystemTime,
+) -> ExportMetricsServiceRequest {
+    let (start_time, time) = (unix_nano(start_time), unix_nano(time));
+    let mut resources = BTreeMap::<Vec<(String, String)>, Vec<OtlpMetric>>::new();
+    for metric_family in metric_families {
+        let mut by_resource = BTreeMap::<_, Vec<_>>::new();
+        for metric in metric_family.get_metric() {
+            let (resource, attributes) = split_attributes(metric_family.get_name(), metric);
+            by_resource
+                .entry(resource)
+                .or_default()
+                .push((attributes, metric));
+        }
+        for (resource, metrics) in by_resource {
+            resources.entry(resource).or_default().push(otlp_metric(
+                metric_family,
+                metrics,
+                start_time,
+                time,
+            ));
+        }
+    }
+    let resource_metrics = resources
+        .into_iter()
+        .map(|(resource, metrics)| ResourceMetrics {
+            resource: Some(Resource {
+                attributes: common_attributes
+                    .iter()
+                    .map(|(key, value)| attribute(key, value))
+                    .chain(resource.iter().map(|(key, value)| attribute(key, value)))
+                    .collect(),
+                dropped_attributes_count: 0,
+            }),
+            scope_metrics: vec![ScopeMetrics {
+                scope: Some(InstrumentationScope {
+                    name: SCOPE_NAME.to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                    ..Default::default()
+                }),
+                metrics,
+                schema_url: String::new(),
+            }],
+            schema_url: String::new(),
+        })
+        .collect();
+    ExportMetricsServiceRequest { resource_metrics }
+}
+
+/// Send the export request to the OTLP gRPC endpoint of the channel.
+pub(crate) async fn export_grpc(
+    channel: Channel,
+    request: Request<ExportMetricsServiceRequest>,
+) -> Result<ExportMetricsServiceResponse, Status> {
+    let mut client = tonic::client::Grpc::new(channel);
+    client
+        .ready()
+        .await
+        .map_err(|error| Status::unavailable(error.to_string()))?;
+    let codec = ProstCodec::<ExportMetricsServiceRequest, ExportMetricsServiceResponse>::default();
+    let response = client
+        .unary(request, PathAndQuery::from_static(EXPORT_PATH), codec)
+        .await?;
+    Ok(response.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{core::Collector, GaugeVec, HistogramOpts, HistogramVec, Opts};
+
+    /// Get the string attributes as pairs.
+    fn pairs(attributes: &[KeyValue]) -> Vec<(&str, &str)> {
+        attributes
+            .iter()
+            .map(|attribute| {
+                let value = match attribute
+                    .value
+                    .as_ref()
+                    .and_then(|value| value.value.as_ref())
+                {
+                    Some(any_value::Value::StringValue(value)) => value.as_str(),
+                    _ => "",
+                };
+                (attribute.key.as_str(), value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_export_request() {
+        let gauge_vec = GaugeVec::new(
+            Opts::new("used_size_bytes", "used").subsystem("diskpool"),
+            &["node", "name"],
+        )
+        .unwrap();
+        gauge_vec.with_label_values(&["node-1", "pool-1"]).set(3.0);
+        gauge_vec.with_label_values(&["node-1", "pool-2"]).set(5.0);
+        let replica_vec = GaugeVec::new(
+            Opts::new("read_latency_us", "latency").subsystem("replica"),
+            &["node", "name", "pv_name"],
+        )
+        .unwrap();
+        replica_vec
+            .with_label_values(&["node-1", "replica-1", "pvc-1"])
+            .set(7.0);
+        let mut metric_families = gauge_vec.collect();
+        metric_families.extend(replica_vec.collect());
+
+        let time = UNIX_EPOCH + std::time::Duration::from_secs(10);
+        let request = export_request(
+            &metric_families,
+            &[("service.name", "mayastor")],
+            UNIX_EPOCH,
+            time,
+        );
+        assert_eq!(request.resource_metrics.len(), 3);
+        let resource = |index: usize| {
+            pairs(
+                &request.resource_metrics[index]
+                    .resource
+                    .as_ref()
+                    .unwrap()
+                    .attributes,
+            )
+        };
+        assert_eq!(
+            resource(0),
+            vec![
+                ("service.name", "mayastor"),
+                (NODE_ATTRIBUTE, "node-1"),
+                (POOL_ATTRIBUTE, "pool-1")
+            ]
+        );
+        assert_eq!(
+            resource(2),
+            vec![
+                ("service.name", "mayastor"),
+                (NODE_ATTRIBUTE, "node-1"),
+                (VOLUME_ATTRIBUTE, "pvc-1")
+            ]
+        );
+        let metric = &request.resource_metrics[2].scope_metrics[0].metrics[0];
+        assert_eq!(metric.name, "replica_read_latency_us");
+        let Some(Data::Gauge(gauge)) = &metric.data else {
+            panic!("Expected a gauge, got {:?}", metric.data);
+        };
+        assert_eq!(
+            pairs(&gauge.data_points[0].attributes),
+            vec![("name", "replica-1")]
+        );
+        assert_eq!(gauge.data_points[0].time_unix_nano, 10_000_000_000);
+        assert_eq!(
+            gauge.data_points[0].value,
+            Some(number_data_point::Value::AsDouble(7.0))
+        );
+    }
+
+    #[test]
+    fn test_export_histogram() {
+        let histogram_vec = HistogramVec::new(
+            HistogramOpts::new("read_latency_seconds", "latency")
+                .subsystem("volume")
+                .buckets(vec![0.001, 0.01, 0.1]),
+            &["node", "pv_name"],
+        )
+        .unwrap();
+        let histogram = histogram_vec.with_label_values(&["node-1", "pvc-1"]);
+        for value in [0.0005, 0.005, 0.005, 0.05, 1.0] {
+            histogram.observe(value);
+        }
+        let request = export_request(&histogram_vec.collect(), &[], UNIX_EPOCH, UNIX_EPOCH);
+        let Some(Data::Histogram(histogram)) =
+            &request.resource_metrics[0].scope_metrics[0].metrics[0].data
+        else {
+            panic!("Expected a histogram");
+        };
+        let point = &histogram.data_points[0];
+        assert_eq!(point.explicit_bounds, vec![0.001, 0.01, 0.1]);
+        assert_eq!(point.bucket_counts, vec![1, 2, 1, 1]);
+        assert_eq!(point.count, 5);
+        assert!(point.attributes.is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+use prometheus::proto::{Metric, MetricFamily, MetricType};
+
+/// Content type of the Prometheus remote-write requests.
+pub(crate) const CONTENT_TYPE: &str = "application/x-protobuf";
+/// Content encoding of the Prometheus remote-write requests.
+pub(crate) const CONTENT_ENCODING: &str = "snappy";
+/// Version of the Prometheus remote-write protocol.
+pub(crate) const VERSION: &str = "0.1.0";
+
+/// Prometheus remote-write request, as defined in `prometheus/prompb/remote.proto`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct WriteRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub(crate) timeseries: Vec<TimeSeries>,
+}
+
+/// Series of samples with the same labels.
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct TimeSeries {
+    #[prost(message, repeated, tag = "1")]
+    pub(crate) labels: Vec<Label>,
+    #[prost(message, repeated, tag = "2")]
+    pub(crate) samples: Vec<Sample>,
+}
+
+/// Label of a series.
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct Label {
+    #[prost(string, tag = "1")]
+    pub(crate) name: String,
+    #[prost(string, tag = "2")]
+    pub(crate) value: String,
+}
+
+/// Value of a series at a point in time.
+#[derive(Clone, PartialEq, prost::Message)]
+pub(crate) struct Sample {
+    #[prost(double, tag = "1")]
+    pub(crate) value: f64,
+    #[prost(int64, tag = "2")]
+    pub(crate) timestamp: i64,
+}
+
+/// Build the series of the metric with the given name suffix and additional label.
+fn time_series(
+    name: String,
+    metric: &Metric,
+    extra_label: Option<(&str, String)>,
+    common_labels: &[(String, String)],
+    value: f64,
+    timestamp: i64,
+) -> TimeSeries {
+    let mut labels = vec![Label {
+        name: "__name__".to_string(),
+        value: name,
+    }];
+    labels.extend(metric.get_label().iter().map(|label| Label {
+        name: label.get_name().to_string(),
+        value: label.get_value().to_string(),
+    }));
+    labels.extend(extra_label.map(|(name, value)| Label {
+        name: name.to_string(),
+        value,
+    }));
+    for (name, value) in common_labels {
+        // The labels of the metric take precedence.
+        if !labels.iter().any(|label| &label.name == name) {
+            labels.push(Label {
+                name: name.clone(),
+                value: value.clone(),
+            });
+        }
+    }
+    // The remote-write protocol requires the labels to be sorted by name.
+    labels.sort_by(|left, right| left.name.cmp(&right.name));
+    TimeSeries {
+        labels,
+        samples: vec![Sample { value, timestamp }],
+    }
+}
+
+/// Convert the metric families to a remote-write request, with the common labels added to all
+/// the series. Histograms and summaries are split into their `_bucket`/quantile, `_sum` and
+/// `_count` series, as when scraped.
+pub(crate) fn write_request(
+    metric_families: &[MetricFamily],
+    common_labels: &[(String, String)],
+    timestamp: i64,
+) -> WriteRequest {
+    let mut timeseries = Vec::new();
+    for metric_family in metric_families {
+        let name = metric_family.get_name();
+        for metric in metric_family.get_metric() {
+            let mut series = |suffix: &str, extra_label: Option<(&str, String)>, value: f64| {
+                timeseries.push(time_series(
+                    format!("{name}{suffix}"),
+                    metric,
+                    extra_label,
+                    common_labels,
+                    value,
+                    timestamp,
+                ))
+            };
+            match metric_family.get_field_type() {
+                MetricType::COUNTER => series("", None, metric.get_counter().get_value()),
+                MetricType::GAUGE => series("", None, metric.get_gauge().get_value()),
+                MetricType::UNTYPED => series("", None, metric.get_untyped().get_value()),
+                MetricType::HISTOGRAM => {
+                    let histogram = metric.get_histogram();
+                    for bucket in histogram.get_bucket() {
+                        series(
+                            "_bucket",
+                            Some(("le", bucket.get_upper_bound().to_string())),
+                            bucket.get_cumulative_count() as f64,
+                        );
+                    }
+                    series(
+                        "_bucket",
+                        Some(("le", "+Inf".to_string())),
+                        histogram.get_sample_count() as f64,
+                    );
+                    series("_sum", None, histogram.get_sample_sum());
+                    series("_count", None, histogram.get_sample_count() as f64);
+                }
+                MetricType::SUMMARY => {
+                    let summary = metric.get_summary();
+                    for quantile in summary.get_quantile() {
+                        series(
+                            "",
+                            Some(("quantile", quantile.get_quantile().to_string())),
+                            quantile.get_value(),
+                        );
+                    }
+                    series("_sum", None, summary.get_sample_sum());
+                    series("_count", None, summary.get_sample_count() as f64);
+                }
+            }
+        }
+    }
+    WriteRequest { timeseries }
+}
+
+/// Encode the remote-write request as snappy compressed protobuf.
+pub(crate) fn encode(request: &WriteRequest) -> Result<Vec<u8>, snap::Error> {
+    snap::raw::Encoder::new().compress_vec(&prost::Message::encode_to_vec(request))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{core::Collector, GaugeVec, Opts};
+
+    #[test]
+    fn test_write_request() {
+        let gauge_vec = GaugeVec::new(Opts::new("used", "used"), &["name"]).unwrap();
+        gauge_vec.with_label_values(&["pool-1"]).set(3.0);
+        let common_labels = vec![
+            ("job".to_string(), "mayastor".to_string()),
+            ("instance".to_string(), "node-1".to_string()),
+        ];
+        let request = write_request(&gauge_vec.collect(), &common_labels, 1000);
+        let encoded = encode(&request).unwrap();
+
+        let decoded = snap::raw::Decoder::new().decompress_vec(&encoded).unwrap();
+        let decoded = <WriteRequest as prost::Message>::decode(decoded.as_slice()).unwrap();
+        assert_eq!(decoded, request);
+        assert_eq!(decoded.timeseries.len(), 1);
+        let labels = decoded.timeseries[0]
+            .labels
+            .iter()
+            .map(|label| (label.name.as_str(), label.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec![
+                ("__name__", "used"),
+                ("instance", "node-1"),
+                ("job", "mayastor"),
+                ("name", "pool-1")
+            ]
+        );
+        assert_eq!(
+            decoded.timeseries[0].samples,
+            vec![Sample {
+                value: 3.0,
+                timestamp: 1000
+            }]
+        );
+    }
+}
@@ -0,0 +1,352 @@
+use crate::error::ExporterError;
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header,
+    HttpResponse,
+};
+use k8s_openapi::api::{
+    authentication::v1::{TokenReview, TokenReviewSpec, UserInfo},
+    authorization::v1::{NonResourceAttributes, SubjectAccessReview, SubjectAccessReviewSpec},
+};
+use kube::{api::PostParams, Api, Client};
+use std::{
+    collections::HashMap,
+    future::{ready, Future, Ready},
+    path::PathBuf,
+    pin::Pin,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
+use tracing::{error, info, warn};
+
+/// Duration for which a token allowed to get a path through the reviews is not reviewed again.
+const TOKEN_REVIEW_TTL: Duration = Duration::from_secs(60);
+/// Maximum number of reviewed tokens and paths remembered.
+const TOKEN_REVIEW_CACHE_SIZE: usize = 1024;
+
+/// Compare the tokens in constant time, to not leak the valid tokens through timing.
+fn tokens_equal(left: &str, right: &str) -> bool {
+    left.len() == right.len()
+        && left
+            .bytes()
+            .zip(right.bytes())
+            .fold(0, |diff, (l, r)| diff | (l ^ r))
+            == 0
+}
+
+/// The tokens loaded from the token file, along with its modification time.
+#[derive(Default)]
+struct LoadedTokens {
+    modified: Option<SystemTime>,
+    tokens: Vec<String>,
+}
+
+/// Bearer tokens read from a file with one token per line, reloaded whenever it is modified.
+struct TokenFile {
+    path: PathBuf,
+    loaded: Mutex<LoadedTokens>,
+}
+
+impl TokenFile {
+    /// Create a new token file, failing if it cannot be read.
+    fn new(path: PathBuf) -> Result<Self, ExporterError> {
+        let token_file = Self {
+            path,
+            loaded: Mutex::new(LoadedTokens::default()),
+        };
+        let tokens = token_file.read()?;
+        if let Ok(mut loaded) = token_file.loaded.lock() {
+            *loaded = tokens;
+        }
+        Ok(token_file)
+    }
+
+    /// Read the tokens from the file.
+    fn read(&self) -> Result<LoadedTokens, ExporterError> {
+        let modified = self
+            .path
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        let tokens = std::fs::read_to_string(&self.path).map_err(|error| {
+            ExporterError::AuthConfigError(format!(
+                "Failed to read {}: {error}",
+                self.path.display()
+            ))
+        })?;
+        Ok(LoadedTokens {
+            modified,
+            tokens: tokens
+                .lines()
+                .map(str::trim)
+                .filter(|token| !token.is_empty())
+                .map(String::from)
+                .collect(),
+        })
+    }
+
+    /// Whether the token is one of the tokens of the file, reloading them if it has been
+    /// modified. The previous tokens are kept if the reload fails.
+    fn contains(&self, token: &str) -> bool {
+        let Ok(mut loaded) = self.loaded.lock() else {
+            return false;
+        };
+        let modified = self
+            .path
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if modified != loaded.modified {
+            match self.read() {
+                Ok(tokens) => {
+                    info!("Reloaded the bearer tokens");
+                    *loaded = tokens;
+                }
+                Err(error) => {
+                    error!(?error, "Failed to reload the bearer tokens");
+                    loaded.modified = modified;
+                }
+            }
+        }
+        loaded.tokens.iter().any(|valid| tokens_equal(valid, token))
+    }
+}
+
+/// Authenticates bearer tokens through the Kubernetes TokenReview API, and authorizes their user
+/// to get the requested path through the SubjectAccessReview API, remembering the tokens allowed
+/// recently along with the path.
+struct TokenReviewer {
+    token_reviews: Api<TokenReview>,
+    access_reviews: Api<SubjectAccessReview>,
+    reviewed: Mutex<HashMap<(String, String), Instant>>,
+}
+
+impl TokenReviewer {
+    /// Whether the token has been allowed to get the path within the TTL.
+    fn recently_allowed(&self, token: &str, path: &str) -> bool {
+        let Ok(reviewed) = self.reviewed.lock() else {
+            return false;
+        };
+        reviewed
+            .get(&(token.to_string(), path.to_string()))
+            .is_some_and(|time| time.elapsed() < TOKEN_REVIEW_TTL)
+    }
+
+    /// Get the user authenticated by the Kubernetes API server with the token.
+    async fn authenticate(&self, token: &str) -> Option<UserInfo> {
+        let review = TokenReview {
+            spec: TokenReviewSpec {
+                token: Some(token.to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        match self
+            .token_reviews
+            .create(&PostParams::default(), &review)
+            .await
+        {
+            Ok(review) => review
+                .status
+                .filter(|status| status.authenticated.unwrap_or(false))
+                .and_then(|status| status.user),
+            Err(error) => {
+                error!(%error, "Error while creating TokenReview");
+                None
+            }
+        }
+    }
+
+    /// Whether the user is authorized by the Kubernetes API server to get the path, eg: through
+    /// a ClusterRole allowing `get` on the `/metrics` nonResourceURLs.
+    async fn authorize(&self, user: UserInfo, path: &str) -> bool {
+        let review = SubjectAccessReview {
+            spec: SubjectAccessReviewSpec {
+                user: user.username,
+                groups: user.groups,
+                uid: user.uid,
+                extra: user.extra,
+                non_resource_attributes: Some(NonResourceAttributes {
+                    path: Some(path.to_string()),
+                    verb: Some("get".to_string()),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        match self
+            .access_reviews
+            .create(&PostParams::default(), &review)
+            .await
+        {
+            Ok(review) => review.status.is_some_and(|status| status.allowed),
+            Err(error) => {
+                error!(%error, "Error while creating SubjectAccessReview");
+                false
+            }
+        }
+    }
+
+    /// Whether the token authenticates a user allowed to get the path.
+    async fn review(&self, token: &str, path: &str) -> bool {
+        if self.recently_allowed(token, path) {
+            return true;
+        }
+        let allowed = match self.authenticate(token).await {
+            Some(user) => self.authorize(user, path).await,
+            None => false,
+        };
+        if allowed {
+            if let Ok(mut reviewed) = self.reviewed.lock() {
+                reviewed.retain(|_, time| time.elapsed() < TOKEN_REVIEW_TTL);
+                if reviewed.len() < TOKEN_REVIEW_CACHE_SIZE {
+                    reviewed.insert((token.to_string(), path.to_string()), Instant::now());
+                }
+            }
+        }
+        allowed
+    }
+}
+
+/// Authenticates the bearer tokens of the requests against a token file and/or the Kubernetes
+/// TokenReview and SubjectAccessReview APIs.
+pub(crate) struct Authenticator {
+    token_file: Option<TokenFile>,
+    token_reviewer: Option<TokenReviewer>,
+}
+
+impl Authenticator {
+    /// Create a new authenticator, if any of the authentication methods is enabled.
+    pub(crate) async fn new(
+        token_file: Option<PathBuf>,
+        token_review: bool,
+    ) -> Result<Option<Self>, ExporterError> {
+        let token_file = token_file.map(TokenFile::new).transpose()?;
+        let token_reviewer = match token_review {
+            true => {
+                let client = Client::try_default().await.map_err(|error| {
+                    ExporterError::AuthConfigError(format!(
+                        "Failed to create Kubernetes client: {error}"
+                    ))
+                })?;
+                Some(TokenReviewer {
+                    token_reviews: Api::all(client.clone()),
+                    access_reviews: Api::all(client),
+                    reviewed: Mutex::new(HashMap::new()),
+                })
+            }
+            false => None,
+        };
+        if token_file.is_none() && token_reviewer.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            token_file,
+            token_reviewer,
+        }))
+    }
+
+    /// Whether the bearer token is valid to get the path.
+    async fn authenticate(&self, token: &str, path: &str) -> bool {
+        if let Some(token_file) = &self.token_file {
+            if token_file.contains(token) {
+                return true;
+            }
+        }
+        match &self.token_reviewer {
+            Some(token_reviewer) => token_reviewer.review(token, path).await,
+            None => false,
+        }
+    }
+}
+
+/// Get the bearer token from the Authorization header of the request.
+fn bearer_token(request: &ServiceRequest) -> Option<String> {
+    let value = request
+        .headers()
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("bearer")
+        .then(|| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// Middleware rejecting the requests without a valid bearer token, when an authenticator is
+/// configured.
+#[derive(Clone, Default)]
+pub(crate) struct BearerAuth {
+    authenticator: Option<Arc<Authenticator>>,
+}
+
+impl BearerAuth {
+    /// Create the middleware with the authenticator, letting all requests through if none.
+    pub(crate) fn new(authenticator: Option<Arc<Authenticator>>) -> Self {
+        Self { authenticator }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for BearerAuth
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Transform = BearerAuthMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(BearerAuthMiddleware {
+            service: Rc::new(service),
+            authenticator: self.authenticator.clone(),
+        }))
+    }
+}
+
+/// Service of the BearerAuth middleware.
+pub(crate) struct BearerAuthMiddleware<S> {
+    service: Rc<S>,
+    authenticator: Option<Arc<Authenticator>>,
+}
+
+impl<S, B> Service<ServiceRequest> for BearerAuthMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let authenticator = self.authenticator.clone();
+        Box::pin(async move {
+            if let Some(authenticator) = authenticator {
+                let authenticated = match bearer_token(&request) {
+                    Some(token) => authenticator.authenticate(&token, request.path()).await,
+                    None => false,
+                };
+                if !authenticated {
+                    warn!(peer = ?request.peer_addr(), "Rejecting unauthenticated metrics request");
+                    let response = HttpResponse::Unauthorized()
+                        .insert_header((header::WWW_AUTHENTICATE, "Bearer"))
+                        .finish();
+                    return Ok(request.into_response(response).map_into_right_body());
+                }
+            }
+            service
+                .call(request)
+                .await
+                .map(ServiceResponse::map_into_left_body)
+        })
+    }
+}
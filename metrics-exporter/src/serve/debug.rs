@@ -0,0 +1,119 @@
+use crate::cache::{Cache, Data};
+use actix_web::{web, HttpResponse};
+use serde::Serialize;
+use std::{collections::BTreeMap, sync::Mutex};
+use tracing::error;
+
+/// Cached data of a node, along with the time of the last update of each resource type.
+#[derive(Serialize)]
+struct NodeCache<'a> {
+    /// Time of the last successful update of each resource type, in RFC 3339 format.
+    last_updated: BTreeMap<String, String>,
+    #[serde(flatten)]
+    data: &'a Data,
+}
+
+impl<'a> From<&'a Data> for NodeCache<'a> {
+    fn from(data: &'a Data) -> Self {
+        let last_updated = data
+            .exporter_stats()
+            .last_success()
+            .iter()
+            .map(|(resource, time)| {
+                (
+                    resource.as_ref().to_string(),
+                    humantime::format_rfc3339(*time).to_string(),
+                )
+            })
+            .collect();
+        Self { last_updated, data }
+    }
+}
+
+/// Respond with the cache, or with an internal error if it cannot be locked.
+fn with_cache(cache: &Mutex<Cache>, respond: impl FnOnce(&Cache) -> HttpResponse) -> HttpResponse {
+    match cache.lock() {
+        Ok(cache) => respond(&cache),
+        Err(error) => {
+            error!(%error, "Error while getting cache resource");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// Handler for the cache debug endpoint. Serves the cached data of each node as JSON.
+pub(crate) async fn cache_handler(cache: web::Data<Mutex<Cache>>) -> HttpResponse {
+    with_cache(&cache, |cache| {
+        let nodes = cache
+            .nodes()
+            .map(|(node, data)| (node, NodeCache::from(data)))
+            .collect::<BTreeMap<_, _>>();
+        HttpResponse::Ok().json(nodes)
+    })
+}
+
+/// Handler for the gRPC debug endpoint. Serves the last response and error of each gRPC method
+/// of each node as JSON.
+pub(crate) async fn grpc_handler(cache: web::Data<Mutex<Cache>>) -> HttpResponse {
+    with_cache(&cache, |cache| {
+        let nodes = cache
+            .nodes()
+            .map(|(node, data)| (node, data.grpc_exchanges()))
+            .collect::<BTreeMap<_, _>>();
+        HttpResponse::Ok().json(nodes)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cache::{store_node_data, Cache},
+        client::fake::FakeIoEngine,
+        config::ExporterConfig,
+        serve::{auth::BearerAuth, debug_route, metric_route},
+        Cli,
+    };
+    use actix_web::{test, App};
+    use clap::Parser;
+    use rpc::v1::pool;
+    use serde_json::Value;
+    use std::sync::Arc;
+
+    #[actix_web::test]
+    async fn test_debug_endpoints() {
+        let args = Cli::parse_from(["metrics-exporter", "--disable-collectors=exporter"]);
+        let config = Arc::new(ExporterConfig::try_from(&args).unwrap());
+        let fake = FakeIoEngine {
+            pools: vec![pool::Pool {
+                name: "pool-1".to_string(),
+                capacity: 10737418240,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let client = fake.serve().with_exchanges();
+        let cache = Cache::new_shared();
+        store_node_data(&client, &cache, "worker-0", &config).await;
+
+        let app = test::init_service(App::new().configure(|cfg| {
+            metric_route(cfg, BearerAuth::new(None), cache.clone(), config.clone());
+            debug_route(cfg, BearerAuth::new(None));
+        }))
+        .await;
+
+        let request = test::TestRequest::get().uri("/debug/cache").to_request();
+        let node_cache: Value = test::call_and_read_body_json(&app, request).await;
+        let node_cache = &node_cache["worker-0"];
+        assert!(node_cache["last_updated"]["pool"].is_string());
+        assert_eq!(node_cache["pools"]["pools"][0]["name"], "pool-1");
+
+        let request = test::TestRequest::get().uri("/debug/grpc").to_request();
+        let exchanges: Value = test::call_and_read_body_json(&app, request).await;
+        let exchanges = &exchanges["worker-0"];
+        let list_pools = exchanges["ListPools"]["last_response"]["value"]
+            .as_str()
+            .unwrap();
+        assert!(list_pools.contains("pool-1"));
+        assert!(exchanges["ListPools"]["last_error"].is_null());
+    }
+}
@@ -0,0 +1,357 @@
+use crate::collector::exemplar::{bucket_exemplar, counter_exemplar, Exemplar};
+use actix_web::{
+    http::header::{Accept, Header},
+    HttpRequest,
+};
+use prometheus::{
+    proto::{LabelPair, MetricFamily, MetricType},
+    Encoder, ProtobufEncoder, TextEncoder,
+};
+use std::{collections::HashSet, fmt::Write};
+
+/// Content type of the OpenMetrics text format.
+const OPENMETRICS_TEXT_FORMAT: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Exposition formats in which the metrics can be served.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExpositionFormat {
+    /// Prometheus text format.
+    Text,
+    /// OpenMetrics text format.
+    OpenMetrics,
+    /// Prometheus delimited protobuf format.
+    Protobuf,
+}
+
+impl ExpositionFormat {
+    /// Select the most preferred format from the Accept header of the request, falling back to
+    /// the Prometheus text format.
+    pub(crate) fn negotiate(request: &HttpRequest) -> Self {
+        let Ok(accept) = Accept::parse(request) else {
+            return Self::Text;
+        };
+        accept
+            .ranked()
+            .iter()
+            .find_map(
+                |mime| match (mime.type_().as_str(), mime.subtype().as_str()) {
+                    ("application", "openmetrics-text") => Some(Self::OpenMetrics),
+                    ("application", "vnd.google.protobuf")
+                        if mime
+                            .get_param("proto")
+                            .is_some_and(|proto| proto == "io.prometheus.client.MetricFamily")
+                            && mime
+                                .get_param("encoding")
+                                .is_some_and(|encoding| encoding == "delimited") =>
+                    {
+                        Some(Self::Protobuf)
+                    }
+                    ("text", "plain") | ("text", "*") | ("*", "*") => Some(Self::Text),
+                    _ => None,
+                },
+            )
+            .unwrap_or(Self::Text)
+    }
+
+    /// Get the content type of the format.
+    pub(crate) fn content_type(&self) -> String {
+        match self {
+            Self::Text => TextEncoder::new().format_type().to_string(),
+            Self::OpenMetrics => OPENMETRICS_TEXT_FORMAT.to_string(),
+            Self::Protobuf => ProtobufEncoder::new().format_type().to_string(),
+        }
+    }
+
+    /// Encode the metric families in the format.
+    pub(crate) fn encode(&self, metric_families: &[MetricFamily]) -> prometheus::Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        match self {
+            Self::Text => TextEncoder::new().encode(metric_families, &mut buffer)?,
+            Self::OpenMetrics => buffer = encode_openmetrics(metric_families).into_bytes(),
+            Self::Protobuf => ProtobufEncoder::new().encode(metric_families, &mut buffer)?,
+        }
+        Ok(buffer)
+    }
+}
+
+/// Encode the metric families in the OpenMetrics text format, along with the exemplars of the
+/// counters and histogram buckets.
+fn encode_openmetrics(metric_families: &[MetricFamily]) -> String {
+    // OpenMetrics names the counter families without their `_total` suffix, which is the name of
+    // the deprecated io stat gauges, so these are left out.
+    let counters = metric_families
+        .iter()
+        .filter(|family| family.get_field_type() == MetricType::COUNTER)
+        .filter_map(|family| family.get_name().strip_suffix("_total"))
+        .collect::<HashSet<_>>();
+    let mut output = String::new();
+    for family in metric_families {
+        let name = family.get_name();
+        if family.get_field_type() != MetricType::COUNTER && counters.contains(name) {
+            continue;
+        }
+        let (family_name, metric_type) = match family.get_field_type() {
+            MetricType::COUNTER => (name.strip_suffix("_total").unwrap_or(name), "counter"),
+            MetricType::GAUGE => (name, "gauge"),
+            MetricType::HISTOGRAM => (name, "histogram"),
+            MetricType::SUMMARY => (name, "summary"),
+            MetricType::UNTYPED => (name, "unknown"),
+        };
+        let _ = writeln!(output, "# TYPE {family_name} {metric_type}");
+        let _ = writeln!(
+            output,
+            "# HELP {family_name} {}",
+            escape(family.get_help(), false)
+        );
+        for metric in family.get_metric() {
+            let labels = metric.get_label();
+            match family.get_field_type() {
+                MetricType::COUNTER => {
+                    let sample = format!("{family_name}_total");
+                    write_sample(
+                        &mut output,
+                        &sample,
+                        labels,
+                        None,
+                        metric.get_counter().get_value(),
+                        counter_exemplar(metric),
+                    );
+                }
+                MetricType::GAUGE => {
+                    write_sample(
+                        &mut output,
+                        name,
+                        labels,
+                        None,
+                        metric.get_gauge().get_value(),
+                        None,
+                    );
+                }
+                MetricType::UNTYPED => {
+                    write_sample(
+                        &mut output,
+                        name,
+                        labels,
+                        None,
+                        metric.get_untyped().get_value(),
+                        None,
+                    );
+                }
+                MetricType::HISTOGRAM => {
+                    let histogram = metric.get_histogram();
+                    let bucket = format!("{name}_bucket");
+                    let mut has_inf_bucket = false;
+                    for b in histogram.get_bucket() {
+                        has_inf_bucket |= b.get_upper_bound().is_infinite();
+                        let le = format_value(b.get_upper_bound());
+                        let extra = ("le", le.as_str());
+                        let count = b.get_cumulative_count() as f64;
+                        let exemplar = bucket_exemplar(b);
+                        write_sample(&mut output, &bucket, labels, Some(extra), count, exemplar);
+                    }
+                    if !has_inf_bucket {
+                        let count = histogram.get_sample_count() as f64;
+                        let inf = Some(("le", "+Inf"));
+                        write_sample(&mut output, &bucket, labels, inf, count, None);
+                    }
+                    let count = histogram.get_sample_count() as f64;
+                    write_sample(
+                        &mut output,
+                        &format!("{name}_count"),
+                        labels,
+                        None,
+                        count,
+                        None,
+                    );
+                    let sum = histogram.get_sample_sum();
+                    write_sample(&mut output, &format!("{name}_sum"), labels, None, sum, None);
+                }
+                MetricType::SUMMARY => {
+                    let summary = metric.get_summary();
+                    for q in summary.get_quantile() {
+                        let quantile = format_value(q.get_quantile());
+                        let extra = ("quantile", quantile.as_str());
+                        write_sample(&mut output, name, labels, Some(extra), q.get_value(), None);
+                    }
+                    let count = summary.get_sample_count() as f64;
+                    write_sample(
+                        &mut output,
+                        &format!("{name}_count"),
+                        labels,
+                        None,
+                        count,
+                        None,
+                    );
+                    let sum = summary.get_sample_sum();
+                    write_sample(&mut output, &format!("{name}_sum"), labels, None, sum, None);
+                }
+            }
+        }
+    }
+    output.push_str("# EOF\n");
+    output
+}
+
+/// Write a sample line with its labels, an optional additional label and an optional exemplar.
+fn write_sample(
+    output: &mut String,
+    name: &str,
+    labels: &[LabelPair],
+    extra_label: Option<(&str, &str)>,
+    value: f64,
+    exemplar: Option<Exemplar>,
+) {
+    let labels = labels
+        .iter()
+        .map(|label| (label.get_name(), label.get_value()))
+        .chain(extra_label)
+        .map(|(name, value)| format!("{name}=\"{}\"", escape(value, true)))
+        .collect::<Vec<_>>();
+    if labels.is_empty() {
+        let _ = write!(output, "{name} {}", format_value(value));
+    } else {
+        let _ = write!(
+            output,
+            "{name}{{{}}} {}",
+            labels.join(","),
+            format_value(value)
+        );
+    }
+    if let Some(exemplar) = exemplar {
+        let labels = exemplar
+            .label
+            .iter()
+            .map(|label| format!("{}=\"{}\"", label.name, escape(&label.value, true)))
+            .collect::<Vec<_>>();
+        let _ = write!(
+            output,
+            " # {{{}}} {}",
+            labels.join(","),
+            format_value(exemplar.value)
+        );
+        if let Some(timestamp) = exemplar.timestamp_secs() {
+            let _ = write!(output, " {}", format_value(timestamp));
+        }
+    }
+    output.push('\n');
+}
+
+/// Format a sample value as OpenMetrics expects it.
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escape backslashes and new lines, and also double quotes for label values.
+fn escape(value: &str, quotes: bool) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '"' if quotes => escaped.push_str("\\\""),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_openmetrics, ExpositionFormat};
+    use crate::collector::exemplar::{set_bucket_exemplar, set_counter_exemplar, Exemplar};
+    use actix_web::{http::header, test::TestRequest};
+    use prometheus::{CounterVec, Encoder, Gauge, Histogram, HistogramOpts, Opts, Registry};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn test_negotiate() {
+        let negotiate = |accept: Option<&str>| {
+            let request = match accept {
+                Some(accept) => TestRequest::default().insert_header((header::ACCEPT, accept)),
+                None => TestRequest::default(),
+            };
+            ExpositionFormat::negotiate(&request.to_http_request())
+        };
+        assert_eq!(negotiate(None), ExpositionFormat::Text);
+        assert_eq!(
+            negotiate(Some("text/plain;version=0.0.4")),
+            ExpositionFormat::Text
+        );
+        assert_eq!(
+            negotiate(Some("application/openmetrics-text;version=1.0.0,application/openmetrics-text;version=0.0.1;q=0.75,text/plain;version=0.0.4;q=0.5,*/*;q=0.1")),
+            ExpositionFormat::OpenMetrics
+        );
+        assert_eq!(
+            negotiate(Some("application/vnd.google.protobuf;proto=io.prometheus.client.MetricFamily;encoding=delimited;q=0.7,text/plain;version=0.0.4;q=0.3")),
+            ExpositionFormat::Protobuf
+        );
+        assert_eq!(
+            negotiate(Some(
+                "application/vnd.google.protobuf;proto=other,text/plain;q=0.3"
+            )),
+            ExpositionFormat::Text
+        );
+    }
+
+    #[test]
+    fn test_encode_openmetrics() {
+        let registry = Registry::new();
+        let counter =
+            CounterVec::new(Opts::new("bytes_read_total", "Total \"bytes\""), &["name"]).unwrap();
+        counter.with_label_values(&["pool-1"]).inc_by(42.0);
+        // A deprecated gauge named as the counter without its suffix.
+        let gauge = Gauge::new("bytes_read", "Total bytes").unwrap();
+        gauge.set(42.0);
+        let histogram = Histogram::with_opts(
+            HistogramOpts::new("latency_seconds", "Latency").buckets(vec![0.5]),
+        )
+        .unwrap();
+        histogram.observe(0.25);
+        registry.register(Box::new(counter)).unwrap();
+        registry.register(Box::new(gauge)).unwrap();
+        registry.register(Box::new(histogram)).unwrap();
+
+        let mut metric_families = registry.gather();
+        let time = UNIX_EPOCH + Duration::from_millis(1700000000500);
+        let counter_exemplar = Exemplar::new(&[], 2.0, time);
+        set_counter_exemplar(&mut metric_families[1].mut_metric()[0], &counter_exemplar);
+        let bucket = &mut metric_families[2].mut_metric()[0]
+            .mut_histogram()
+            .mut_bucket()[0];
+        set_bucket_exemplar(
+            bucket,
+            &Exemplar::new(&[("ops", "4".to_string())], 0.25, time),
+        );
+
+        assert_eq!(
+            encode_openmetrics(&metric_families),
+            "# TYPE bytes_read counter\n\
+             # HELP bytes_read Total \"bytes\"\n\
+             bytes_read_total{name=\"pool-1\"} 42 # {} 2 1700000000.5\n\
+             # TYPE latency_seconds histogram\n\
+             # HELP latency_seconds Latency\n\
+             latency_seconds_bucket{le=\"0.5\"} 1 # {ops=\"4\"} 0.25 1700000000.5\n\
+             latency_seconds_bucket{le=\"+Inf\"} 1\n\
+             latency_seconds_count 1\n\
+             latency_seconds_sum 0.25\n\
+             # EOF\n"
+        );
+        // The protobuf encoder must accept the same families, along with their exemplars.
+        let mut buffer = Vec::new();
+        prometheus::ProtobufEncoder::new()
+            .encode(&metric_families, &mut buffer)
+            .unwrap();
+        let counter_exemplar = prost::Message::encode_to_vec(&counter_exemplar);
+        assert!(buffer
+            .windows(counter_exemplar.len())
+            .any(|window| window == counter_exemplar));
+    }
+}
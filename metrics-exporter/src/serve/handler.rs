@@ -0,0 +1,173 @@
+use super::encoder::ExpositionFormat;
+use crate::{cache::Cache, collector::registry::gather, config::ExporterConfig};
+use actix_web::{http::header, web, HttpRequest, HttpResponse, Responder};
+use std::sync::Mutex;
+use tracing::error;
+
+/// Handler for metrics. Gathers the metrics of the enabled collectors and serves the cached data
+/// over Http, in the exposition format negotiated through the Accept header.
+pub(crate) async fn metrics_handler(
+    request: HttpRequest,
+    cache: web::Data<Mutex<Cache>>,
+    config: web::Data<ExporterConfig>,
+) -> impl Responder {
+    let metric_families = gather(&cache.into_inner(), &config);
+    let format = ExpositionFormat::negotiate(&request);
+    // Encodes the data into the buffer.
+    let buffer = match format.encode(&metric_families) {
+        Ok(buffer) => buffer,
+        Err(error) => {
+            error!(%error, "Could not encode custom metrics");
+            Vec::new()
+        }
+    };
+    HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, format.content_type()))
+        .body(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cache::{store_node_data, Cache},
+        client::fake::FakeIoEngine,
+        config::ExporterConfig,
+        serve::{auth::BearerAuth, metric_route},
+        Cli,
+    };
+    use actix_web::{http::header, test, App};
+    use clap::Parser;
+    use rpc::v1::{host, nexus, pool, stats};
+    use std::sync::Arc;
+
+    /// Metrics served for the fake io-engine, rewritten by running the tests with `UPDATE_GOLDEN`.
+    const GOLDEN_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/serve/testdata/metrics.txt"
+    );
+
+    /// Metrics served for the fake io-engine in the OpenMetrics format.
+    const OPENMETRICS_GOLDEN_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/serve/testdata/metrics.openmetrics.txt"
+    );
+
+    const VOLUME: &str = "ec4e66fd-3b33-4439-b504-d49aba53da26";
+    const REPLICA: &str = "b4cb3f73-5c4a-4eae-8ef3-0f4fbd5fa8bb";
+
+    fn io_stats(name: &str, scale: u64) -> stats::IoStats {
+        stats::IoStats {
+            name: name.to_string(),
+            num_read_ops: 100 * scale,
+            bytes_read: 409600 * scale,
+            num_write_ops: 50 * scale,
+            bytes_written: 3276800 * scale,
+            read_latency_ticks: 20000 * scale,
+            write_latency_ticks: 40000 * scale,
+            tick_rate: 1000000,
+            ..Default::default()
+        }
+    }
+
+    fn fake_io_engine() -> FakeIoEngine {
+        let rebuilding_child = "nvmf://10.1.0.5:8420/nqn.2019-05.io.openebs:b4cb3f73".to_string();
+        FakeIoEngine {
+            pools: vec![pool::Pool {
+                name: "pool-1".to_string(),
+                disks: vec!["aio:///dev/sdb?uuid=7d1a6bf4".to_string()],
+                state: 1,
+                capacity: 10737418240,
+                used: 4294967296,
+                committed: 6442450944,
+                ..Default::default()
+            }],
+            block_devices: vec![host::BlockDevice {
+                devname: "sdb".to_string(),
+                devpath: "/devices/pci0000:00/0000:00:05.0/block/sdb".to_string(),
+                devlinks: vec!["/dev/disk/by-id/ata-QEMU_HARDDISK_QM00005".to_string()],
+                model: "QEMU_HARDDISK".to_string(),
+                size: 20971520,
+                connection_type: "sata".to_string(),
+                is_rotational: false,
+                ..Default::default()
+            }],
+            nexuses: vec![nexus::Nexus {
+                name: VOLUME.to_string(),
+                uuid: VOLUME.to_string(),
+                state: 2,
+                ana_state: 1,
+                rebuilds: 1,
+                children: vec![
+                    nexus::Child {
+                        uri: format!("bdev:///{REPLICA}?uuid={REPLICA}"),
+                        state: 1,
+                        rebuild_progress: -1,
+                        ..Default::default()
+                    },
+                    nexus::Child {
+                        uri: rebuilding_child.clone(),
+                        state: 2,
+                        rebuild_progress: 40,
+                        reason: 8,
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }],
+            rebuild_stats: vec![nexus::RebuildStatsResponse {
+                nexus_uuid: VOLUME.to_string(),
+                uri: rebuilding_child,
+                blocks_total: 1000,
+                blocks_transferred: 400,
+                blocks_remaining: 600,
+                block_size: 4096,
+                progress: 40,
+                is_partial: true,
+                ..Default::default()
+            }],
+            pool_stats: vec![io_stats("pool-1", 2)],
+            nexus_stats: vec![io_stats(VOLUME, 1)],
+            replica_stats: vec![stats::ReplicaIoStats {
+                entity_id: Some(VOLUME.to_string()),
+                stats: Some(io_stats(REPLICA, 1)),
+            }],
+        }
+    }
+
+    /// Scrape the metrics of the fake io-engine with the Accept header, if any, and compare them
+    /// with the golden file.
+    async fn assert_golden(accept: Option<&str>, golden_file: &str) {
+        // The exporter health metrics depend on the time of the requests.
+        let args = Cli::parse_from(["metrics-exporter", "--disable-collectors=exporter"]);
+        let config = Arc::new(ExporterConfig::try_from(&args).unwrap());
+        let client = fake_io_engine().serve();
+        let cache = Cache::new_shared();
+        store_node_data(&client, &cache, "worker-0", &config).await;
+
+        let app = test::init_service(App::new().configure(|cfg| {
+            metric_route(cfg, BearerAuth::new(None), cache.clone(), config.clone())
+        }))
+        .await;
+        let mut request = test::TestRequest::get().uri("/metrics");
+        if let Some(accept) = accept {
+            request = request.insert_header((header::ACCEPT, accept));
+        }
+        let body = test::call_and_read_body(&app, request.to_request()).await;
+        let metrics = String::from_utf8(body.to_vec()).unwrap();
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::write(golden_file, &metrics).unwrap();
+        }
+        assert_eq!(metrics, std::fs::read_to_string(golden_file).unwrap());
+    }
+
+    #[actix_web::test]
+    async fn test_metrics_golden() {
+        assert_golden(None, GOLDEN_FILE).await;
+    }
+
+    #[actix_web::test]
+    async fn test_metrics_openmetrics_golden() {
+        let accept = "application/openmetrics-text;version=1.0.0,text/plain;q=0.5";
+        assert_golden(Some(accept), OPENMETRICS_GOLDEN_FILE).await;
+    }
+}
@@ -0,0 +1,40 @@
+use crate::{cache::SharedCache, config::ExporterConfig};
+use actix_web::web;
+use auth::BearerAuth;
+use std::sync::Arc;
+/// module for bearer token authentication.
+pub(crate) mod auth;
+/// module for the debug handlers.
+mod debug;
+/// module for exposition format encoders.
+pub(crate) mod encoder;
+/// module for prometheus handlers.
+mod handler;
+/// module for the TLS configuration.
+pub(crate) mod tls;
+
+pub(crate) fn metric_route(
+    cfg: &mut web::ServiceConfig,
+    auth: BearerAuth,
+    cache: SharedCache,
+    config: Arc<ExporterConfig>,
+) {
+    cfg.app_data(web::Data::from(cache))
+        .app_data(web::Data::from(config))
+        .service(
+            web::resource("/metrics")
+                .wrap(auth)
+                .route(web::get().to(handler::metrics_handler)),
+        );
+}
+
+/// Route the debug endpoints, serving the cached data and the last gRPC exchanges as JSON. The
+/// cache is the one registered by the metric route.
+pub(crate) fn debug_route(cfg: &mut web::ServiceConfig, auth: BearerAuth) {
+    cfg.service(
+        web::scope("/debug")
+            .wrap(auth)
+            .route("/cache", web::get().to(debug::cache_handler))
+            .route("/grpc", web::get().to(debug::grpc_handler)),
+    );
+}
@@ -0,0 +1,161 @@
+use crate::error::ExporterError;
+use rustls::{
+    server::{AllowAnyAuthenticatedClient, ClientHello, ResolvesServerCert},
+    sign::CertifiedKey,
+    Certificate, PrivateKey, RootCertStore, ServerConfig,
+};
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::SystemTime,
+};
+use tracing::{error, info};
+
+/// Read all the certificates from the PEM file at the given path.
+fn read_certs(path: &Path) -> Result<Vec<Certificate>, ExporterError> {
+    let file = File::open(path).map_err(|error| {
+        ExporterError::TlsConfigError(format!("Failed to open {}: {error}", path.display()))
+    })?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(file)).map_err(|error| {
+        ExporterError::TlsConfigError(format!("Failed to parse {}: {error}", path.display()))
+    })?;
+    if certs.is_empty() {
+        return Err(ExporterError::TlsConfigError(format!(
+            "No certificate found in {}",
+            path.display()
+        )));
+    }
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+/// Read the first private key from the PEM file at the given path.
+fn read_key(path: &Path) -> Result<PrivateKey, ExporterError> {
+    let file = File::open(path).map_err(|error| {
+        ExporterError::TlsConfigError(format!("Failed to open {}: {error}", path.display()))
+    })?;
+    let items = rustls_pemfile::read_all(&mut BufReader::new(file)).map_err(|error| {
+        ExporterError::TlsConfigError(format!("Failed to parse {}: {error}", path.display()))
+    })?;
+    items
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            ExporterError::TlsConfigError(format!("No private key found in {}", path.display()))
+        })
+}
+
+/// Get the modification time of the file at the given path.
+fn modified(path: &Path) -> Option<SystemTime> {
+    path.metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// The certificate and key loaded from the files, along with their modification times.
+struct LoadedCert {
+    modified: (Option<SystemTime>, Option<SystemTime>),
+    certified_key: Arc<CertifiedKey>,
+}
+
+/// Resolves the server certificate from the certificate and key files, reloading them whenever
+/// they are modified, eg: when the Secret they are mounted from is renewed.
+pub(crate) struct ReloadingCertResolver {
+    cert_file: PathBuf,
+    key_file: PathBuf,
+    loaded: RwLock<LoadedCert>,
+}
+
+impl ReloadingCertResolver {
+    /// Create a new resolver, failing if the certificate and key cannot be loaded.
+    pub(crate) fn new(cert_file: PathBuf, key_file: PathBuf) -> Result<Self, ExporterError> {
+        let loaded = Self::load(&cert_file, &key_file)?;
+        Ok(Self {
+            cert_file,
+            key_file,
+            loaded: RwLock::new(loaded),
+        })
+    }
+
+    /// Load the certificate and key from the files.
+    fn load(cert_file: &Path, key_file: &Path) -> Result<LoadedCert, ExporterError> {
+        let modified = (modified(cert_file), modified(key_file));
+        let certs = read_certs(cert_file)?;
+        let key = rustls::sign::any_supported_type(&read_key(key_file)?).map_err(|error| {
+            ExporterError::TlsConfigError(format!(
+                "Unsupported private key in {}: {error}",
+                key_file.display()
+            ))
+        })?;
+        Ok(LoadedCert {
+            modified,
+            certified_key: Arc::new(CertifiedKey::new(certs, key)),
+        })
+    }
+
+    /// Get the current certificate and key, reloading them if the files have been modified.
+    /// The previous certificate and key are kept if the reload fails.
+    fn certified_key(&self) -> Option<Arc<CertifiedKey>> {
+        let modified = (modified(&self.cert_file), modified(&self.key_file));
+        {
+            let loaded = self.loaded.read().ok()?;
+            if loaded.modified == modified {
+                return Some(loaded.certified_key.clone());
+            }
+        }
+        let mut loaded = self.loaded.write().ok()?;
+        match Self::load(&self.cert_file, &self.key_file) {
+            Ok(reloaded) => {
+                info!("Reloaded the TLS certificate and key");
+                *loaded = reloaded;
+            }
+            Err(error) => {
+                error!(?error, "Failed to reload the TLS certificate and key");
+                // Don't retry on every handshake until the files are modified again.
+                loaded.modified = modified;
+            }
+        }
+        Some(loaded.certified_key.clone())
+    }
+}
+
+impl ResolvesServerCert for ReloadingCertResolver {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        self.certified_key()
+    }
+}
+
+/// Build the TLS configuration of the metrics server, requiring clients to present a certificate
+/// signed by the client CA, if provided.
+pub(crate) fn server_config(
+    cert_file: PathBuf,
+    key_file: PathBuf,
+    client_ca_file: Option<PathBuf>,
+) -> Result<ServerConfig, ExporterError> {
+    let resolver = Arc::new(ReloadingCertResolver::new(cert_file, key_file)?);
+    let builder = ServerConfig::builder().with_safe_defaults();
+    let config = match client_ca_file {
+        Some(client_ca_file) => {
+            let mut roots = RootCertStore::empty();
+            for cert in read_certs(&client_ca_file)? {
+                roots.add(&cert).map_err(|error| {
+                    ExporterError::TlsConfigError(format!(
+                        "Invalid client CA certificate in {}: {error}",
+                        client_ca_file.display()
+                    ))
+                })?;
+            }
+            builder
+                .with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots).boxed())
+                .with_cert_resolver(resolver)
+        }
+        None => builder.with_no_client_auth().with_cert_resolver(resolver),
+    };
+    Ok(config)
+}
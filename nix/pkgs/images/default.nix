@@ -2,7 +2,7 @@
 # avoid dependency on docker tool chain. Though the maturity of OCI
 # builder in nixpkgs is questionable which is why we postpone this step.
 
-{ pkgs, dockerTools, lib, extensions, busybox, gnupg, kubernetes-helm-wrapped, semver-tool, yq-go, runCommand, sourcer, img_tag ? "", img_org ? "", img_prefix }:
+{ pkgs, dockerTools, lib, extensions, busybox, kubernetes-helm-wrapped, semver-tool, yq-go, runCommand, sourcer, img_tag ? "", img_org ? "", img_prefix }:
 let
   repo-org = if img_org != "" then img_org else "${builtins.readFile (pkgs.runCommand "repo_org" {
     buildInputs = with pkgs; [ git ];
@@ -84,13 +84,10 @@ let
     build-extensions-image rec{
       inherit buildType;
       package = extensions.${buildType}.obs.callhome;
-      copyToRoot = [ ./../../../call-home/assets busybox gnupg ];
-      extraCommands = ''
-        mkdir -p encryption_dir
-      '';
+      copyToRoot = [ ./../../../call-home/assets busybox ];
       pname = package.pname;
       config = {
-        Env = [ "KEY_FILEPATH=/key/public.gpg" "ENCRYPTION_DIR=/encryption_dir" ];
+        Env = [ "KEY_FILEPATH=/key/public.gpg" ];
       };
     };
 